@@ -0,0 +1,22 @@
+use std::env;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rustc-check-cfg=cfg(enumflags2_core_error)");
+
+    // `core::error::Error` is stable since Rust 1.81.
+    if rustc_minor_version().map_or(false, |minor| minor >= 81) {
+        println!("cargo:rustc-cfg=enumflags2_core_error");
+    }
+}
+
+fn rustc_minor_version() -> Option<u32> {
+    let rustc = env::var_os("RUSTC")?;
+    let output = Command::new(rustc).arg("--version").output().ok()?;
+    let version = std::str::from_utf8(&output.stdout).ok()?;
+    let mut pieces = version.split('.');
+    if pieces.next() != Some("rustc 1") {
+        return None;
+    }
+    pieces.next()?.parse().ok()
+}
@@ -4,6 +4,8 @@ extern crate proc_macro;
 extern crate quote;
 
 use proc_macro2::{Span, TokenStream};
+use quote::ToTokens;
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use syn::{
     parse::{Parse, ParseStream},
@@ -16,6 +18,8 @@ struct Flag<'a> {
     name: Ident,
     span: Span,
     value: FlagValue<'a>,
+    params: FlagParameters,
+    doc: Option<String>,
 }
 
 enum FlagValue<'a> {
@@ -30,25 +34,278 @@ impl FlagValue<'_> {
     }
 }
 
-struct Parameters {
-    default: Vec<Ident>,
+/// The parameters of a single flag, given with `#[bitflag(...)]`.
+#[derive(Default)]
+struct FlagParameters {
+    previously: Vec<Previously>,
+    rename: Option<syn::LitStr>,
+    aliases: Vec<syn::LitStr>,
+    symbol: Option<syn::LitChar>,
+    label: Option<syn::LitStr>,
 }
 
-impl Parse for Parameters {
+/// `#[bitflag(previously = 1 << 4, until = 2)]`: before version 2,
+/// the flag had the value `1 << 4`.
+struct Previously {
+    value: Expr,
+    until: Expr,
+}
+
+impl Parse for FlagParameters {
     fn parse(input: ParseStream) -> syn::parse::Result<Self> {
-        if input.is_empty() {
-            return Ok(Parameters { default: vec![] });
+        const EXPECTED: &str =
+            "expected `previously`, `until`, `rename`, `alias`, `symbol` or `label`";
+        let mut params = FlagParameters::default();
+        let mut previously = None;
+        let mut until = None;
+        let span = input.span();
+        while !input.is_empty() {
+            let param: Ident = input
+                .parse()
+                .map_err(|err| syn::Error::new(err.span(), EXPECTED))?;
+            input.parse::<Token![=]>()?;
+            if param == "previously" {
+                previously = Some(input.parse()?);
+            } else if param == "until" {
+                until = Some(input.parse()?);
+            } else if param == "rename" {
+                if params.rename.is_some() {
+                    return Err(syn::Error::new_spanned(param, "duplicate `rename`"));
+                }
+                params.rename = Some(input.parse()?);
+            } else if param == "alias" {
+                params.aliases.push(input.parse()?);
+            } else if param == "symbol" {
+                if params.symbol.is_some() {
+                    return Err(syn::Error::new_spanned(param, "duplicate `symbol`"));
+                }
+                params.symbol = Some(input.parse()?);
+            } else if param == "label" {
+                if params.label.is_some() {
+                    return Err(syn::Error::new_spanned(param, "duplicate `label`"));
+                }
+                params.label = Some(input.parse()?);
+            } else {
+                return Err(syn::Error::new_spanned(param, EXPECTED));
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        match (previously, until) {
+            (Some(value), Some(until)) => params.previously.push(Previously { value, until }),
+            (None, None) => {}
+            _ => {
+                return Err(syn::Error::new(
+                    span,
+                    "`previously` and `until` must be given together",
+                ))
+            }
+        }
+
+        Ok(params)
+    }
+}
+
+impl FlagParameters {
+    /// Remove the `#[bitflag(...)]` attributes from a variant, and parse them.
+    fn take_from(attrs: &mut Vec<syn::Attribute>) -> Result<Self, syn::Error> {
+        let mut params = FlagParameters::default();
+        let mut result = Ok(());
+        attrs.retain(|attr| {
+            if !attr.path().is_ident("bitflag") {
+                return true;
+            }
+
+            match attr.parse_args::<FlagParameters>() {
+                Ok(parsed) => {
+                    if let (Some(_), Some(rename)) = (&params.rename, &parsed.rename) {
+                        result = Err(syn::Error::new_spanned(rename, "duplicate `rename`"));
+                    }
+                    if let (Some(_), Some(symbol)) = (&params.symbol, &parsed.symbol) {
+                        result = Err(syn::Error::new_spanned(symbol, "duplicate `symbol`"));
+                    }
+                    if let (Some(_), Some(label)) = (&params.label, &parsed.label) {
+                        result = Err(syn::Error::new_spanned(label, "duplicate `label`"));
+                    }
+                    params.previously.extend(parsed.previously);
+                    params.rename = params.rename.take().or(parsed.rename);
+                    params.aliases.extend(parsed.aliases);
+                    params.symbol = params.symbol.take().or(parsed.symbol);
+                    params.label = params.label.take().or(parsed.label);
+                }
+                Err(err) => result = Err(err),
+            }
+            false
+        });
+
+        result.map(|()| params)
+    }
+}
+
+/// A naming convention for `#[bitflags(rename_all = "...")]`, with the same
+/// meaning as in serde.
+#[derive(Clone, Copy)]
+enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    const ALL: &'static [(&'static str, RenameRule)] = &[
+        ("lowercase", RenameRule::Lower),
+        ("UPPERCASE", RenameRule::Upper),
+        ("PascalCase", RenameRule::Pascal),
+        ("camelCase", RenameRule::Camel),
+        ("snake_case", RenameRule::Snake),
+        ("SCREAMING_SNAKE_CASE", RenameRule::ScreamingSnake),
+        ("kebab-case", RenameRule::Kebab),
+        ("SCREAMING-KEBAB-CASE", RenameRule::ScreamingKebab),
+    ];
+
+    fn parse(lit: &syn::LitStr) -> Result<Self, syn::Error> {
+        let value = lit.value();
+        Self::ALL
+            .iter()
+            .find(|(name, _)| *name == value)
+            .map(|&(_, rule)| rule)
+            .ok_or_else(|| {
+                let names = Self::ALL.iter().map(|(name, _)| format!("`{}`", name));
+                let names = names.collect::<Vec<_>>().join(", ");
+                syn::Error::new_spanned(lit, format!("unknown case, expected one of {}", names))
+            })
+    }
+
+    /// Apply the rule to a `PascalCase` variant name.
+    fn apply(self, variant: &str) -> String {
+        let snake = || {
+            let mut snake = String::new();
+            for (i, c) in variant.char_indices() {
+                if i > 0 && c.is_uppercase() {
+                    snake.push('_');
+                }
+                snake.extend(c.to_lowercase());
+            }
+            snake
+        };
+
+        match self {
+            RenameRule::Lower => variant.to_lowercase(),
+            RenameRule::Upper => variant.to_uppercase(),
+            RenameRule::Pascal => variant.to_owned(),
+            RenameRule::Camel => {
+                let mut chars = variant.chars();
+                chars.next().map_or(String::new(), |first| {
+                    first.to_lowercase().chain(chars).collect()
+                })
+            }
+            RenameRule::Snake => snake(),
+            RenameRule::ScreamingSnake => snake().to_uppercase(),
+            RenameRule::Kebab => snake().replace('_', "-"),
+            RenameRule::ScreamingKebab => snake().replace('_', "-").to_uppercase(),
         }
+    }
+}
+
+#[derive(Default)]
+struct Parameters {
+    default: Vec<Ident>,
+    reserved: Vec<Reserved>,
+    subset_of: Option<syn::Path>,
+    rename_all: Option<RenameRule>,
+    display: bool,
+    from_str: bool,
+    try_from: bool,
+    /// `accessors`, optionally with the name of the trait: `accessors = Name`.
+    accessors: Option<Option<Ident>>,
+    /// `bools`, optionally with the name of the struct: `bools = Name`.
+    bools: Option<Option<Ident>>,
+}
+
+/// A bit that isn't a flag, but has a name for diagnostics:
+/// `reserved(Name = 1 << 4)`.
+struct Reserved {
+    name: Ident,
+    value: Expr,
+}
 
-        input.parse::<Token![default]>()?;
+impl Parse for Reserved {
+    fn parse(input: ParseStream) -> syn::parse::Result<Self> {
+        let name = input.parse()?;
         input.parse::<Token![=]>()?;
-        let mut default = vec![input.parse()?];
+        let value = input.parse()?;
+        Ok(Reserved { name, value })
+    }
+}
+
+impl Parse for Parameters {
+    fn parse(input: ParseStream) -> syn::parse::Result<Self> {
+        let mut params = Parameters::default();
         while !input.is_empty() {
-            input.parse::<Token![|]>()?;
-            default.push(input.parse()?);
+            if input.peek(Token![default]) {
+                input.parse::<Token![default]>()?;
+                input.parse::<Token![=]>()?;
+                params.default.push(input.parse()?);
+                while !input.is_empty() && !input.peek(Token![,]) {
+                    input.parse::<Token![|]>()?;
+                    params.default.push(input.parse()?);
+                }
+            } else {
+                const EXPECTED: &str = "expected `default`, `reserved`, `subset_of`, \
+                                        `rename_all`, `display`, `from_str`, `try_from`, \
+                                        `accessors` or `bools`";
+                let param: Ident = input
+                    .parse()
+                    .map_err(|err| syn::Error::new(err.span(), EXPECTED))?;
+                if param == "reserved" {
+                    let content;
+                    syn::parenthesized!(content in input);
+                    params
+                        .reserved
+                        .extend(content.parse_terminated(Reserved::parse, Token![,])?);
+                } else if param == "subset_of" {
+                    input.parse::<Token![=]>()?;
+                    params.subset_of = Some(input.parse()?);
+                } else if param == "rename_all" {
+                    input.parse::<Token![=]>()?;
+                    params.rename_all = Some(RenameRule::parse(&input.parse()?)?);
+                } else if param == "display" {
+                    params.display = true;
+                } else if param == "from_str" {
+                    params.from_str = true;
+                } else if param == "try_from" {
+                    params.try_from = true;
+                } else if param == "accessors" || param == "bools" {
+                    let name = if input.peek(Token![=]) {
+                        input.parse::<Token![=]>()?;
+                        Some(input.parse()?)
+                    } else {
+                        None
+                    };
+                    if param == "accessors" {
+                        params.accessors = Some(name);
+                    } else {
+                        params.bools = Some(name);
+                    }
+                } else {
+                    return Err(syn::Error::new_spanned(param, EXPECTED));
+                }
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
         }
 
-        Ok(Parameters { default })
+        Ok(params)
     }
 }
 
@@ -57,9 +314,9 @@ pub fn bitflags_internal(
     attr: proc_macro::TokenStream,
     input: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
-    let Parameters { default } = parse_macro_input!(attr as Parameters);
+    let params = parse_macro_input!(attr as Parameters);
     let mut ast = parse_macro_input!(input as DeriveInput);
-    let output = gen_enumflags(&mut ast, default);
+    let output = gen_enumflags(&mut ast, params);
 
     output
         .unwrap_or_else(|err| {
@@ -72,6 +329,491 @@ pub fn bitflags_internal(
         .into()
 }
 
+struct FlagExprInput {
+    ty: syn::Path,
+    expr: Expr,
+}
+
+impl Parse for FlagExprInput {
+    fn parse(input: ParseStream) -> syn::parse::Result<Self> {
+        let ty = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let expr = input.parse()?;
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+        }
+
+        Ok(FlagExprInput { ty, expr })
+    }
+}
+
+#[proc_macro]
+pub fn flag_expr_internal(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let FlagExprInput { ty, expr } = parse_macro_input!(input as FlagExprInput);
+    let bits = Ident::new("__enumflags2_bits", Span::mixed_site());
+
+    match gen_flag_expr(&ty, &expr, &bits) {
+        Ok(body) => quote! {
+            |flags: ::enumflags2::BitFlags<#ty>| -> bool {
+                let #bits = flags.bits();
+                #body
+            }
+        },
+        Err(err) => err.to_compile_error(),
+    }
+    .into()
+}
+
+/// Translate a boolean expression over flag names into one over the bits
+/// of a `BitFlags`.
+fn gen_flag_expr(ty: &syn::Path, expr: &Expr, bits: &Ident) -> Result<TokenStream, syn::Error> {
+    match expr {
+        Expr::Path(path) if path.attrs.is_empty() && path.qself.is_none() => {
+            let variant = path.path.get_ident().ok_or_else(|| {
+                syn::Error::new_spanned(path, "expected the name of a flag")
+            })?;
+
+            Ok(quote_spanned!(variant.span() =>
+                ((#bits & (#ty::#variant as <#ty as ::enumflags2::_internal::RawBitFlags>::Numeric)) != 0)
+            ))
+        }
+        Expr::Paren(syn::ExprParen { expr, .. }) | Expr::Group(syn::ExprGroup { expr, .. }) => {
+            gen_flag_expr(ty, expr, bits)
+        }
+        Expr::Unary(syn::ExprUnary { op: syn::UnOp::Not(_), expr, .. }) => {
+            let inner = gen_flag_expr(ty, expr, bits)?;
+            Ok(quote!(!(#inner)))
+        }
+        Expr::Binary(binary) => {
+            let left = gen_flag_expr(ty, &binary.left, bits)?;
+            let right = gen_flag_expr(ty, &binary.right, bits)?;
+            match binary.op {
+                syn::BinOp::BitOr(_) => Ok(quote!((#left || #right))),
+                syn::BinOp::BitAnd(_) => Ok(quote!((#left && #right))),
+                syn::BinOp::BitXor(_) => Ok(quote!((#left != #right))),
+                _ => Err(syn::Error::new_spanned(
+                    &binary.op,
+                    "only `|`, `&` and `^` can be used in a flag expression",
+                )),
+            }
+        }
+        _ => Err(syn::Error::new_spanned(
+            expr,
+            "expected a flag name, `!`, `|`, `&`, `^` or parentheses",
+        )),
+    }
+}
+
+enum MatchMode {
+    Exact,
+    Contains,
+    Any,
+}
+
+enum MatchPattern {
+    Flags {
+        mode: MatchMode,
+        ty: Ident,
+        variants: Vec<Ident>,
+    },
+    Wildcard,
+}
+
+struct MatchArm {
+    pattern: MatchPattern,
+    span: Span,
+    body: TokenStream,
+}
+
+/// The input of `bitflags_match!`, after the arms have been separated by
+/// the `macro_rules!` wrapper: `(scrutinee) $([pattern] (body))*`.
+struct MatchInput {
+    scrutinee: TokenStream,
+    arms: Vec<MatchArm>,
+}
+
+impl Parse for MatchPattern {
+    fn parse(input: ParseStream) -> syn::parse::Result<Self> {
+        if input.peek(Token![_]) {
+            input.parse::<Token![_]>()?;
+            return Ok(MatchPattern::Wildcard);
+        }
+
+        let mode: Ident = input.parse()?;
+        let mode = if mode == "exact" {
+            MatchMode::Exact
+        } else if mode == "contains" {
+            MatchMode::Contains
+        } else if mode == "any" {
+            MatchMode::Any
+        } else {
+            return Err(syn::Error::new_spanned(
+                mode,
+                "expected `exact`, `contains`, `any` or `_`",
+            ));
+        };
+
+        let ty = input.parse()?;
+        input.parse::<Token![::]>()?;
+        let variants = if input.peek(syn::token::Brace) {
+            let content;
+            syn::braced!(content in input);
+            content
+                .parse_terminated(Ident::parse, Token![|])?
+                .into_iter()
+                .collect()
+        } else {
+            vec![input.parse()?]
+        };
+
+        Ok(MatchPattern::Flags { mode, ty, variants })
+    }
+}
+
+impl Parse for MatchInput {
+    fn parse(input: ParseStream) -> syn::parse::Result<Self> {
+        let content;
+        syn::parenthesized!(content in input);
+        let scrutinee = content.parse()?;
+
+        let mut arms = vec![];
+        while !input.is_empty() {
+            let pattern;
+            syn::bracketed!(pattern in input);
+            let span = pattern.span();
+            let pattern = pattern.parse()?;
+            let body;
+            syn::parenthesized!(body in input);
+            let body = body.parse()?;
+
+            arms.push(MatchArm { pattern, span, body });
+        }
+
+        Ok(MatchInput { scrutinee, arms })
+    }
+}
+
+/// Wrap the body of an arm in braces, unless it already is a block.
+fn block(body: &TokenStream) -> TokenStream {
+    use proc_macro2::{Delimiter, TokenTree};
+
+    let mut tokens = body.clone().into_iter();
+    match (tokens.next(), tokens.next()) {
+        (Some(TokenTree::Group(group)), None) if group.delimiter() == Delimiter::Brace => {
+            group.into_token_stream()
+        }
+        (Some(TokenTree::Group(group)), None) if group.delimiter() == Delimiter::None => {
+            block(&group.stream())
+        }
+        _ => quote!({ #body }),
+    }
+}
+
+#[proc_macro]
+pub fn bitflags_match_internal(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as MatchInput);
+    gen_bitflags_match(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+fn gen_bitflags_match(input: MatchInput) -> Result<TokenStream, syn::Error> {
+    let MatchInput { scrutinee, arms } = input;
+
+    // All the arms must name the same type.
+    let mut ty: Option<&Ident> = None;
+    for arm in &arms {
+        if let MatchPattern::Flags { ty: arm_ty, .. } = &arm.pattern {
+            match ty {
+                None => ty = Some(arm_ty),
+                Some(ty) if ty == arm_ty => {}
+                Some(ty) => {
+                    return Err(syn::Error::new_spanned(
+                        arm_ty,
+                        format!("expected `{}`, like in the previous arms", ty),
+                    ))
+                }
+            }
+        }
+    }
+
+    let ty = ty.ok_or_else(|| {
+        syn::Error::new(
+            Span::call_site(),
+            "bitflags_match! needs at least one arm that names the flags",
+        )
+    })?;
+
+    let bits = Ident::new("__enumflags2_bits", Span::mixed_site());
+    let numeric = quote!(<#ty as ::enumflags2::_internal::RawBitFlags>::Numeric);
+    let internal = quote!(::enumflags2::_internal);
+
+    let mut descriptors = vec![];
+    let mut conditions = vec![];
+    let mut bodies = vec![];
+    let mut wildcard = None;
+    for arm in &arms {
+        let (descriptor, condition) = match &arm.pattern {
+            MatchPattern::Flags { mode, variants, .. } => {
+                let mask = quote!((0 #(| (#ty::#variants as #numeric))*));
+                match mode {
+                    MatchMode::Exact => (
+                        quote!(#internal::MatchArm::Exact(#mask as u128)),
+                        quote!(#bits == #mask),
+                    ),
+                    MatchMode::Contains => (
+                        quote!(#internal::MatchArm::Contains(#mask as u128)),
+                        quote!((#bits & #mask) == #mask),
+                    ),
+                    MatchMode::Any => (
+                        quote!(#internal::MatchArm::Any(#mask as u128)),
+                        quote!((#bits & #mask) != 0),
+                    ),
+                }
+            }
+            MatchPattern::Wildcard => {
+                // Any arms after the first wildcard get reported as unreachable.
+                if wildcard.is_none() {
+                    wildcard = Some(&arm.body);
+                }
+                (quote!(#internal::MatchArm::Wildcard), quote!(true))
+            }
+        };
+
+        descriptors.push(descriptor);
+        if wildcard.is_none() {
+            conditions.push(condition);
+            bodies.push(block(&arm.body));
+        }
+    }
+
+    let arms_const = Ident::new("__ENUMFLAGS2_ARMS", Span::mixed_site());
+    let all_const = Ident::new("__ENUMFLAGS2_ALL", Span::mixed_site());
+    let reachable = arms.iter().enumerate().map(|(i, arm)| {
+        quote_spanned!(arm.span =>
+            const _:
+                <<[(); #internal::match_arm_reachable(#arms_const, #i, #all_const) as usize]
+                    as #internal::AssertionHelper>::Status
+                    as #internal::MatchArmIsReachable>::X
+                = ();
+        )
+    });
+
+    let (exhaustive, fallback) = match wildcard {
+        Some(body) => (quote!(), block(body)),
+        None => (
+            quote!(
+                const _:
+                    <<[(); !#internal::match_arm_reachable(#arms_const, #arms_const.len(), #all_const) as usize]
+                        as #internal::AssertionHelper>::Status
+                        as #internal::MatchIsExhaustive>::X
+                    = ();
+            ),
+            quote!({ #internal::match_unreachable() }),
+        ),
+    };
+
+    Ok(quote! {
+        {
+            const _: () = {
+                const #arms_const: &[#internal::MatchArm] = &[#(#descriptors),*];
+                const #all_const: u128 =
+                    <#ty as ::enumflags2::_internal::RawBitFlags>::ALL_BITS as u128;
+                #(#reachable)*
+                #exhaustive
+            };
+
+            let #bits: #numeric = ::enumflags2::BitFlags::<#ty>::from(#scrutinee).bits();
+            #(if #conditions #bodies else)* #fallback
+        }
+    })
+}
+
+/// An entry of `translate_flags!`: `Name`, `Source => Target`,
+/// `Source => _` or `_ => Target`. A missing side marks a flag that is
+/// dropped by the translation.
+struct TranslationEntry {
+    source: Option<Ident>,
+    target: Option<Ident>,
+}
+
+struct TranslationInput {
+    source: syn::Path,
+    target: syn::Path,
+    bidirectional: bool,
+    entries: Vec<TranslationEntry>,
+}
+
+impl Parse for TranslationEntry {
+    fn parse(input: ParseStream) -> syn::parse::Result<Self> {
+        let optional_flag = |input: ParseStream| -> syn::parse::Result<Option<Ident>> {
+            if input.peek(Token![_]) {
+                input.parse::<Token![_]>()?;
+                Ok(None)
+            } else {
+                input.parse().map(Some)
+            }
+        };
+
+        let source = optional_flag(input)?;
+        let target = if input.peek(Token![=>]) {
+            input.parse::<Token![=>]>()?;
+            optional_flag(input)?
+        } else {
+            source.clone()
+        };
+
+        if source.is_none() && target.is_none() {
+            return Err(input.error("expected the name of a flag"));
+        }
+
+        Ok(TranslationEntry { source, target })
+    }
+}
+
+impl Parse for TranslationInput {
+    fn parse(input: ParseStream) -> syn::parse::Result<Self> {
+        let source = input.parse()?;
+        let bidirectional = if input.peek(Token![<=]) {
+            input.parse::<Token![<=]>()?;
+            input.parse::<Token![>]>()?;
+            true
+        } else {
+            input.parse::<Token![=>]>()?;
+            false
+        };
+        let target = input.parse()?;
+
+        let content;
+        syn::braced!(content in input);
+        let entries = content
+            .parse_terminated(TranslationEntry::parse, Token![,])?
+            .into_iter()
+            .collect();
+
+        Ok(TranslationInput {
+            source,
+            target,
+            bidirectional,
+            entries,
+        })
+    }
+}
+
+#[proc_macro]
+pub fn translate_flags_internal(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as TranslationInput);
+    gen_translate_flags(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+fn gen_translate_flags(input: TranslationInput) -> Result<TokenStream, syn::Error> {
+    let TranslationInput {
+        source,
+        target,
+        bidirectional,
+        entries,
+    } = input;
+
+    if !bidirectional {
+        if let Some(entry) = entries.iter().find(|entry| entry.source.is_none()) {
+            return Err(syn::Error::new_spanned(
+                &entry.target,
+                "flags without a source can only be listed in a `<=>` translation",
+            ));
+        }
+    }
+
+    let sources = entries.iter().flat_map(|e| &e.source).collect::<Vec<_>>();
+    let targets = entries.iter().flat_map(|e| &e.target).collect::<Vec<_>>();
+    let source_checks = gen_translation_checks(&source, &sources, true);
+    let target_checks = gen_translation_checks(&target, &targets, bidirectional);
+
+    let forward = gen_translation_impl(&source, &target, &entries, |e| (&e.source, &e.target));
+    let backward = if bidirectional {
+        gen_translation_impl(&target, &source, &entries, |e| (&e.target, &e.source))
+    } else {
+        quote!()
+    };
+
+    Ok(quote! {
+        const _: () = {
+            #source_checks
+            #target_checks
+            #forward
+            #backward
+        };
+    })
+}
+
+/// Check that no flag of `ty` is listed twice and, if `complete` is set,
+/// that all of them are listed.
+fn gen_translation_checks(ty: &syn::Path, flags: &[&Ident], complete: bool) -> TokenStream {
+    let internal = quote!(::enumflags2::_internal);
+    let count = flags.len() as u32;
+    let listed = quote!((0u128 #(| (#ty::#flags as u128))*));
+
+    let one_to_one = quote_spanned!(ty.span() =>
+        const _:
+            <<[(); (#listed.count_ones() == #count) as usize]
+                as #internal::AssertionHelper>::Status
+                as #internal::TranslationIsOneToOne>::X
+            = ();
+    );
+
+    let covers_all = if complete {
+        quote_spanned!(ty.span() =>
+            const _:
+                <<[(); (#listed == <#ty as #internal::RawBitFlags>::ALL_BITS as u128) as usize]
+                    as #internal::AssertionHelper>::Status
+                    as #internal::TranslationCoversAllFlags>::X
+                = ();
+        )
+    } else {
+        quote!()
+    };
+
+    quote!(#one_to_one #covers_all)
+}
+
+/// Implement `TranslateFlags<to> for from`, taking the `(from, to)` sides
+/// of each entry from `sides`.
+fn gen_translation_impl(
+    from: &syn::Path,
+    to: &syn::Path,
+    entries: &[TranslationEntry],
+    sides: impl Fn(&TranslationEntry) -> (&Option<Ident>, &Option<Ident>),
+) -> TokenStream {
+    let internal = quote!(::enumflags2::_internal);
+    let from_numeric = quote!(<#from as #internal::RawBitFlags>::Numeric);
+    let to_numeric = quote!(<#to as #internal::RawBitFlags>::Numeric);
+
+    let mut lossy = vec![];
+    let mut moves = vec![];
+    for entry in entries {
+        match sides(entry) {
+            (Some(source), Some(target)) => moves.push(quote!(
+                ((((bits & (#from::#source as #from_numeric)) != 0) as #to_numeric)
+                    << (#to::#target as #to_numeric).trailing_zeros())
+            )),
+            (Some(source), None) => lossy.push(source),
+            (None, _) => {}
+        }
+    }
+
+    quote! {
+        impl ::enumflags2::TranslateFlags<#to> for #from {
+            const LOSSY: #from_numeric = 0 #(| (#from::#lossy as #from_numeric))*;
+
+            #[inline]
+            fn translate_bits(bits: #from_numeric) -> #to_numeric {
+                0 #(| #moves)*
+            }
+        }
+    }
+}
+
 /// Try to evaluate the expression given.
 fn fold_expr(expr: &syn::Expr) -> Option<u128> {
     match expr {
@@ -106,6 +848,8 @@ fn collect_flags<'a>(
                 ));
             }
 
+            let params = FlagParameters::take_from(&mut variant.attrs)?;
+            let doc = doc_comment(&variant.attrs);
             let name = variant.ident.clone();
             let span = variant.span();
             let value = if let Some(ref expr) = variant.discriminant {
@@ -118,11 +862,41 @@ fn collect_flags<'a>(
                 FlagValue::Inferred(variant)
             };
 
-            Ok(Flag { name, span, value })
+            Ok(Flag {
+                name,
+                span,
+                value,
+                params,
+                doc,
+            })
         })
         .collect()
 }
 
+/// Join the lines of a doc comment, without the space that follows `///`.
+fn doc_comment(attrs: &[syn::Attribute]) -> Option<String> {
+    let lines = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value: Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. }),
+                ..
+            }) => Some(lit.value()),
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').map(str::to_owned).unwrap_or(line))
+        .collect::<Vec<_>>();
+
+    let doc = lines.join("\n");
+    let doc = doc.trim();
+    if doc.is_empty() {
+        None
+    } else {
+        Some(doc.to_owned())
+    }
+}
+
 fn inferred_value(type_name: &Ident, previous_variants: &[Ident], repr: &Ident) -> Expr {
     let tokens = if previous_variants.is_empty() {
         quote!(1)
@@ -241,7 +1015,457 @@ fn check_flag(type_name: &Ident, flag: &Flag, bits: u8) -> Result<Option<TokenSt
     }
 }
 
-fn gen_enumflags(ast: &mut DeriveInput, default: Vec<Ident>) -> Result<TokenStream, syn::Error> {
+/// Implements `SubsetOf<superset>`, checking that each flag has the same bit
+/// in the superset.
+fn gen_subset_of(type_name: &Ident, flags: &[Flag], superset: &syn::Path) -> TokenStream {
+    let checks = flags.iter().map(|flag| {
+        let variant_name = &flag.name;
+        quote_spanned!(flag.span =>
+            const _:
+                <<[(); (
+                    (#superset::#variant_name as u128) == (#type_name::#variant_name as u128)
+                ) as usize] as ::enumflags2::_internal::AssertionHelper>
+                    ::Status as ::enumflags2::_internal::SubsetFlagMatches>::X
+                = ();
+        )
+    });
+
+    quote! {
+        #(#checks)*
+        impl ::enumflags2::SubsetOf<#superset> for #type_name {}
+    }
+}
+
+/// Returns the entries of `RawBitFlags::HISTORY`, and the checks that the
+/// previous values are single bits, and that no two flags had the same
+/// value in any older layout.
+fn gen_history(ident: &Ident, flags: &[Flag], repr: &Ident) -> (Vec<TokenStream>, TokenStream) {
+    let mut history = vec![];
+    let mut checks = vec![];
+    for flag in flags {
+        let variant_name = &flag.name;
+        for Previously { value, until } in &flag.params.previously {
+            history.push(quote!((Self::#variant_name as #repr, #value, #until)));
+            checks.push(quote_spanned!(value.span() =>
+                const _:
+                    <<[(); (
+                        <#repr>::is_power_of_two(#value)
+                    ) as usize] as ::enumflags2::_internal::AssertionHelper>
+                        ::Status as ::enumflags2::_internal::ExactlyOneBitSet>::X
+                    = ();
+            ));
+        }
+    }
+
+    if !history.is_empty() {
+        // Widen the entries of `HISTORY` rather than the values themselves,
+        // which were already type checked there.
+        let raw = quote!(<#ident as ::enumflags2::_internal::RawBitFlags>);
+        let widened = (0..history.len()).map(|i| {
+            quote!((#raw::HISTORY[#i].0 as u128, #raw::HISTORY[#i].1 as u128, #raw::HISTORY[#i].2))
+        });
+        let disjoint = quote! {
+            ::enumflags2::_internal::history_is_disjoint(
+                #raw::ALL_BITS_U128,
+                &[#(#widened),*],
+            )
+        };
+        checks.push(quote_spanned!(ident.span() =>
+            const _:
+                <<[(); #disjoint as usize] as ::enumflags2::_internal::AssertionHelper>
+                    ::Status as ::enumflags2::_internal::HistoricalFlagsAreDisjoint>::X
+                = ();
+        ));
+    }
+
+    (history, quote!(#(#checks)*))
+}
+
+/// Checks that the reserved bits are neither flags nor reserved twice.
+fn gen_reserved_checks(ident: &Ident, reserved: &[Reserved]) -> TokenStream {
+    let raw = quote!(<#ident as ::enumflags2::_internal::RawBitFlags>);
+    let checks = reserved.iter().enumerate().map(|(i, Reserved { value, .. })| {
+        // Compare the entries of `RESERVED_BITS` rather than `value` itself,
+        // so that `value` is only type checked against the repr there.
+        let bits = quote!(#raw::RESERVED_BITS[#i].0 as u128);
+        let not_flag = quote!(::enumflags2::_internal::disjoint(#bits, #raw::ALL_BITS_U128));
+        let distinct = (0..i).map(|j| {
+            quote!(::enumflags2::_internal::disjoint(#bits, #raw::RESERVED_BITS[#j].0 as u128))
+        });
+        quote_spanned!(value.span() =>
+            const _:
+                <<[(); #not_flag as usize] as ::enumflags2::_internal::AssertionHelper>
+                    ::Status as ::enumflags2::_internal::ReservedBitsAreNotFlags>::X
+                = ();
+
+            #(
+                const _:
+                    <<[(); #distinct as usize] as ::enumflags2::_internal::AssertionHelper>
+                        ::Status as ::enumflags2::_internal::ReservedBitsAreDistinct>::X
+                    = ();
+            )*
+        )
+    });
+
+    quote!(#(#checks)*)
+}
+
+/// The entries of the name tables in `RawBitFlags`.
+struct Names {
+    names: Vec<TokenStream>,
+    aliases: Vec<TokenStream>,
+    symbols: Vec<TokenStream>,
+    labels: Vec<TokenStream>,
+    descriptions: Vec<TokenStream>,
+}
+
+/// Returns the entries of `RawBitFlags::NAMES`, `RawBitFlags::ALIASES`,
+/// `RawBitFlags::SYMBOLS`, `RawBitFlags::LABELS` and
+/// `RawBitFlags::DESCRIPTIONS`.
+fn gen_names(
+    flags: &[Flag],
+    repr: &Ident,
+    rename_all: Option<RenameRule>,
+) -> Result<Names, syn::Error> {
+    let mut seen = HashMap::new();
+    let mut check_unique = |name: String, span: Span| match seen.insert(name.clone(), span) {
+        Some(_) => Err(syn::Error::new(span, format!("duplicate flag name `{}`", name))),
+        None => Ok(name),
+    };
+
+    let mut names = vec![];
+    let mut aliases = vec![];
+    let mut symbols = vec![];
+    let mut labels = vec![];
+    let mut descriptions = vec![];
+    for flag in flags {
+        let variant_name = &flag.name;
+        let name = match &flag.params.rename {
+            Some(rename) => check_unique(rename.value(), rename.span())?,
+            None => {
+                let name = syn::ext::IdentExt::unraw(variant_name).to_string();
+                let name = rename_all.map_or(name.clone(), |rule| rule.apply(&name));
+                check_unique(name, flag.span)?
+            }
+        };
+        // `-` stands for the flags that are not set. A symbol taken from
+        // the name is only checked when parsing, so that names starting
+        // with `-` still work for enums that don't use symbols.
+        let symbol = match &flag.params.symbol {
+            Some(symbol) if symbol.value() == '-' => {
+                return Err(syn::Error::new(
+                    symbol.span(),
+                    "`-` can't be the symbol of a flag, since it is the default placeholder",
+                ))
+            }
+            Some(symbol) => symbol.value(),
+            None => name.chars().next().unwrap_or('?'),
+        };
+        let label = match &flag.params.label {
+            Some(label) => label.value(),
+            None => name.clone(),
+        };
+        names.push(quote!((Self::#variant_name as #repr, #name)));
+        symbols.push(quote!((Self::#variant_name as #repr, #symbol)));
+        labels.push(quote!((Self::#variant_name as #repr, #label)));
+        if let Some(doc) = &flag.doc {
+            descriptions.push(quote!((Self::#variant_name as #repr, #doc)));
+        }
+
+        for alias in &flag.params.aliases {
+            let alias = check_unique(alias.value(), alias.span())?;
+            aliases.push(quote!((Self::#variant_name as #repr, #alias)));
+        }
+    }
+
+    Ok(Names {
+        names,
+        aliases,
+        symbols,
+        labels,
+        descriptions,
+    })
+}
+
+/// The `snake_case` name of a variant, for the generated methods and fields.
+/// Unlike `rename_all = "snake_case"`, this doesn't add an underscore
+/// after an existing one, so that `Fast_Path` becomes `fast_path`.
+fn snake_name(variant: &Ident) -> String {
+    let variant = syn::ext::IdentExt::unraw(variant).to_string();
+    let mut snake = String::new();
+    for (i, c) in variant.char_indices() {
+        if i > 0 && c.is_uppercase() && !snake.ends_with('_') {
+            snake.push('_');
+        }
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
+
+/// Turn a `snake_case` name into an identifier, escaping keywords.
+fn snake_ident(name: &str, span: Span) -> Ident {
+    syn::parse_str::<Ident>(name)
+        .map(|ident| Ident::new(&ident.to_string(), span))
+        .unwrap_or_else(|_| Ident::new_raw(name, span))
+}
+
+/// Generates the struct for `#[bitflags(bools)]`, with a `bool` field for
+/// each flag. With serde, the fields are keyed by their own names, unless
+/// the flag was renamed with `rename` or `rename_all`.
+fn gen_bools(
+    ident: &Ident,
+    vis: &syn::Visibility,
+    flags: &[Flag],
+    name: Option<Ident>,
+    rename_all: Option<RenameRule>,
+) -> TokenStream {
+    let struct_name = name.unwrap_or_else(|| format_ident!("{}Bools", ident));
+    let keys = flags.iter().map(|flag| {
+        let variant_name = &flag.name;
+        let key = match (&flag.params.rename, rename_all) {
+            (Some(rename), _) => rename.value(),
+            (None, Some(rule)) => rule.apply(&syn::ext::IdentExt::unraw(variant_name).to_string()),
+            (None, None) => snake_name(variant_name),
+        };
+        quote!((#ident::#variant_name, #key))
+    });
+    let fields = flags.iter().map(|flag| {
+        let variant_name = &flag.name;
+        let snake = snake_name(variant_name);
+        let field = snake_ident(&snake, flag.span);
+        let doc = flag.doc.iter();
+        quote! {
+            #(#[doc = #doc])*
+            #[bitflag(flag = #variant_name)]
+            pub #field: bool,
+        }
+    });
+
+    let struct_doc = format!("The flags of [`{}`], as one `bool` per flag.", ident);
+    quote! {
+        #[doc = #struct_doc]
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ::enumflags2::IntoBitFlags)]
+        #[bitflag(flags = #ident)]
+        #[allow(clippy::struct_excessive_bools)]
+        #vis struct #struct_name {
+            #(#fields)*
+        }
+
+        ::enumflags2::_internal::impl_bools_serde!(#struct_name, #ident, &[#(#keys),*]);
+    }
+}
+
+/// `#[bitflag(flags = Enum)]` on a struct, or `#[bitflag(flag = Variant)]`
+/// on a field.
+struct BoolsAttribute {
+    key: Ident,
+    value: syn::Path,
+}
+
+impl Parse for BoolsAttribute {
+    fn parse(input: ParseStream) -> syn::parse::Result<Self> {
+        let key = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let value = input.parse()?;
+        Ok(BoolsAttribute { key, value })
+    }
+}
+
+/// Find the `#[bitflag(key = ...)]` attribute.
+fn bools_attribute(attrs: &[syn::Attribute], key: &str) -> Result<Option<syn::Path>, syn::Error> {
+    let mut value = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("bitflag")) {
+        let parsed = attr.parse_args::<BoolsAttribute>()?;
+        if parsed.key != key {
+            return Err(syn::Error::new_spanned(parsed.key, format!("expected `{}`", key)));
+        }
+        if value.is_some() {
+            return Err(syn::Error::new_spanned(parsed.key, format!("duplicate `{}`", key)));
+        }
+        value = Some(parsed.value);
+    }
+    Ok(value)
+}
+
+#[proc_macro_derive(IntoBitFlags, attributes(bitflag))]
+pub fn into_bitflags_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    gen_into_bitflags(&ast)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+fn gen_into_bitflags(ast: &DeriveInput) -> Result<TokenStream, syn::Error> {
+    let ident = &ast.ident;
+    let fields = match &ast.data {
+        Data::Struct(syn::DataStruct { fields: syn::Fields::Named(fields), .. }) => &fields.named,
+        _ => return Err(syn::Error::new_spanned(ident,
+            "expected struct with named fields for #[derive(IntoBitFlags)]")),
+    };
+
+    if ast.generics.lt_token.is_some() || ast.generics.where_clause.is_some() {
+        return Err(syn::Error::new_spanned(&ast.generics,
+            "#[derive(IntoBitFlags)] cannot be used on generic structs"));
+    }
+
+    let ty = bools_attribute(&ast.attrs, "flags")?.ok_or_else(|| syn::Error::new_spanned(ident,
+        "missing #[bitflag(flags = ...)] attribute naming the flag type"))?;
+
+    let mut field_names = vec![];
+    let mut flags = vec![];
+    for field in fields {
+        let field_name = field.ident.as_ref().expect("named field");
+        let is_bool = matches!(&field.ty, syn::Type::Path(path)
+            if path.qself.is_none() && path.path.is_ident("bool"));
+        if !is_bool {
+            return Err(syn::Error::new_spanned(&field.ty, "expected a `bool` field"));
+        }
+
+        let flag = match bools_attribute(&field.attrs, "flag")? {
+            Some(flag) => flag,
+            None => {
+                let name = syn::ext::IdentExt::unraw(field_name).to_string();
+                let pascal = name.split('_').map(|word| {
+                    let mut chars = word.chars();
+                    chars.next().map_or(String::new(), |first| {
+                        first.to_uppercase().chain(chars).collect()
+                    })
+                }).collect::<String>();
+                Ident::new(&pascal, field_name.span()).into()
+            }
+        };
+        field_names.push(field_name);
+        flags.push(flag);
+    }
+
+    let count = flags.len() as u32;
+    let bits = quote!(0 #(| (#ty::#flags as u128))*);
+    Ok(quote! {
+        const _: () = {
+            const _:
+                <<[(); (
+                    <u128>::count_ones(#bits) == #count
+                ) as usize] as ::enumflags2::_internal::AssertionHelper>
+                    ::Status as ::enumflags2::_internal::BoolFieldsAreDistinct>::X
+                = ();
+
+            const _:
+                <<[(); (
+                    #bits == <#ty as ::enumflags2::_internal::RawBitFlags>::ALL_BITS_U128
+                ) as usize] as ::enumflags2::_internal::AssertionHelper>
+                    ::Status as ::enumflags2::_internal::BoolFieldsCoverAllFlags>::X
+                = ();
+
+            impl ::enumflags2::_internal::core::convert::From<#ident>
+                for ::enumflags2::BitFlags<#ty>
+            {
+                fn from(bools: #ident) -> Self {
+                    let mut flags = ::enumflags2::BitFlags::empty();
+                    #(flags.set(#ty::#flags, bools.#field_names);)*
+                    flags
+                }
+            }
+
+            impl ::enumflags2::_internal::core::convert::From<::enumflags2::BitFlags<#ty>>
+                for #ident
+            {
+                fn from(flags: ::enumflags2::BitFlags<#ty>) -> Self {
+                    Self {
+                        #(#field_names: flags.contains(#ty::#flags),)*
+                    }
+                }
+            }
+        };
+    })
+}
+
+/// Methods of `BitFlags` that an accessor must not shadow.
+const BITFLAGS_METHODS: &[&str] = &["is_empty", "is_all"];
+
+/// Generates the extension trait for `#[bitflags(accessors)]`, with
+/// `is_flag`, `set_flag` and `with_flag` methods for each flag.
+fn gen_accessors(
+    ident: &Ident,
+    vis: &syn::Visibility,
+    flags: &[Flag],
+    name: Option<Ident>,
+) -> Result<TokenStream, syn::Error> {
+    let trait_name = name.unwrap_or_else(|| format_ident!("{}Accessors", ident));
+
+    let mut seen = HashMap::new();
+    let mut signatures = vec![];
+    let mut bodies = vec![];
+    for flag in flags {
+        let variant_name = &flag.name;
+        let snake = snake_name(variant_name);
+        let is = format_ident!("is_{}", snake);
+        let set = format_ident!("set_{}", snake);
+        let with = format_ident!("with_{}", snake);
+
+        if BITFLAGS_METHODS.contains(&&*is.to_string()) {
+            return Err(syn::Error::new(flag.span, format!(
+                "the accessor `{}` would be shadowed by `BitFlags::{}`", is, is)));
+        }
+        if let Some(other) = seen.insert(snake.clone(), variant_name) {
+            return Err(syn::Error::new(flag.span, format!(
+                "the accessors for `{}` collide with the ones for `{}`", variant_name, other)));
+        }
+
+        let flag_path = format!("[`{}::{}`]", ident, variant_name);
+        let doc = flag.doc.iter();
+        let is_doc = format!("Returns whether {} is set.", flag_path);
+        let set_doc = format!("Inserts or removes {}.", flag_path);
+        let with_doc = format!("Returns the flags with {} inserted.", flag_path);
+        signatures.push(quote! {
+            #[doc = #is_doc]
+            #(#[doc = ""] #[doc = #doc])*
+            fn #is(&self) -> bool;
+            #[doc = #set_doc]
+            fn #set(&mut self, value: bool);
+            #[doc = #with_doc]
+            fn #with(self) -> Self;
+        });
+        bodies.push(quote! {
+            #[inline(always)]
+            fn #is(&self) -> bool {
+                self.contains(#ident::#variant_name)
+            }
+            #[inline(always)]
+            fn #set(&mut self, value: bool) {
+                self.set(#ident::#variant_name, value)
+            }
+            #[inline(always)]
+            fn #with(self) -> Self {
+                self | #ident::#variant_name
+            }
+        });
+    }
+
+    let trait_doc = format!("Accessors for the flags of [`{}`], on `BitFlags<{}>`.", ident, ident);
+    Ok(quote! {
+        #[doc = #trait_doc]
+        #vis trait #trait_name {
+            #(#signatures)*
+        }
+
+        impl #trait_name for ::enumflags2::BitFlags<#ident> {
+            #(#bodies)*
+        }
+    })
+}
+
+fn gen_enumflags(ast: &mut DeriveInput, params: Parameters) -> Result<TokenStream, syn::Error> {
+    let Parameters {
+        default,
+        reserved,
+        subset_of,
+        rename_all,
+        display,
+        from_str,
+        try_from,
+        accessors,
+        bools,
+    } = params;
     let ident = &ast.ident;
 
     let span = Span::call_site();
@@ -283,18 +1507,85 @@ fn gen_enumflags(ast: &mut DeriveInput, default: Vec<Ident>) -> Result<TokenStre
         ));
     }
 
+    let accessors_impl = match accessors {
+        Some(name) => Some(gen_accessors(ident, &ast.vis, &variants, name)?),
+        None => None,
+    };
+    let vis = &ast.vis;
+    let bools_struct = bools.map(|name| gen_bools(ident, vis, &variants, name, rename_all));
+    let subset_impl = subset_of.map(|superset| gen_subset_of(ident, &variants, &superset));
+    let (history, history_checks) = gen_history(ident, &variants, &repr);
+    let Names {
+        names,
+        aliases,
+        symbols,
+        labels,
+        descriptions,
+    } = gen_names(&variants, &repr, rename_all)?;
+
     let std = quote_spanned!(span => ::enumflags2::_internal::core);
+    let display_impl = if display {
+        Some(quote_spanned! {
+            span =>
+                impl #std::fmt::Display for #ident {
+                    fn fmt(&self, fmt: &mut #std::fmt::Formatter<'_>) -> #std::fmt::Result {
+                        ::enumflags2::_internal::fmt_flag_name(*self, fmt)
+                    }
+                }
+        })
+    } else {
+        None
+    };
+    let from_str_impl = if from_str {
+        Some(quote_spanned! {
+            span =>
+                impl #std::str::FromStr for #ident {
+                    type Err = ::enumflags2::ParseFlagError;
+
+                    fn from_str(s: &str) -> #std::result::Result<Self, Self::Err> {
+                        ::enumflags2::_internal::parse_flag_name(s)
+                    }
+                }
+        })
+    } else {
+        None
+    };
+
+    let try_from_impl = if try_from {
+        Some(quote_spanned! {
+            span =>
+                impl #std::convert::TryFrom<::enumflags2::BitFlags<#ident>> for #ident {
+                    type Error = ::enumflags2::SingleFlagError<#ident>;
+
+                    #[inline]
+                    fn try_from(
+                        flags: ::enumflags2::BitFlags<#ident>,
+                    ) -> #std::result::Result<Self, Self::Error> {
+                        ::enumflags2::_internal::single_flag(flags)
+                    }
+                }
+        })
+    } else {
+        None
+    };
+
     let ast_variants = match &ast.data {
         Data::Enum(ref data) => &data.variants,
         _ => unreachable!(),
     };
 
     let variant_names = ast_variants.iter().map(|v| &v.ident).collect::<Vec<_>>();
+    let layout_names = variant_names.iter().map(|name| syn::ext::IdentExt::unraw(*name).to_string());
+    let reserved_checks = gen_reserved_checks(ident, &reserved);
+    let reserved_names = reserved.iter().map(|r| r.name.to_string());
+    let reserved_values = reserved.iter().map(|r| &r.value);
 
     Ok(quote_spanned! {
         span =>
             #ast
             #(#deferred)*
+            #history_checks
+            #reserved_checks
             impl #std::ops::Not for #ident {
                 type Output = ::enumflags2::BitFlags<Self>;
                 #[inline(always)]
@@ -342,14 +1633,63 @@ fn gen_enumflags(ast: &mut DeriveInput, default: Vec<Ident>) -> Result<TokenStre
                 const ALL_BITS: <Self as ::enumflags2::_internal::RawBitFlags>::Numeric =
                     0 #(| (Self::#variant_names as #repr))*;
 
+                const ALL_BITS_U128: u128 =
+                    <Self as ::enumflags2::_internal::RawBitFlags>::ALL_BITS as u128;
+
                 const BITFLAGS_TYPE_NAME : &'static str =
                     concat!("BitFlags<", stringify!(#ident), ">");
 
+                const RESERVED_BITS: &'static [(
+                    <Self as ::enumflags2::_internal::RawBitFlags>::Numeric,
+                    &'static str,
+                )] = &[#((#reserved_values, #reserved_names)),*];
+
+                const HISTORY: &'static [(
+                    <Self as ::enumflags2::_internal::RawBitFlags>::Numeric,
+                    <Self as ::enumflags2::_internal::RawBitFlags>::Numeric,
+                    u32,
+                )] = &[#(#history),*];
+
+                const NAMES: &'static [(
+                    <Self as ::enumflags2::_internal::RawBitFlags>::Numeric,
+                    &'static str,
+                )] = &[#(#names),*];
+
+                const ALIASES: &'static [(
+                    <Self as ::enumflags2::_internal::RawBitFlags>::Numeric,
+                    &'static str,
+                )] = &[#(#aliases),*];
+
+                const SYMBOLS: &'static [(
+                    <Self as ::enumflags2::_internal::RawBitFlags>::Numeric,
+                    char,
+                )] = &[#(#symbols),*];
+
+                const LABELS: &'static [(
+                    <Self as ::enumflags2::_internal::RawBitFlags>::Numeric,
+                    &'static str,
+                )] = &[#(#labels),*];
+
+                const DESCRIPTIONS: &'static [(
+                    <Self as ::enumflags2::_internal::RawBitFlags>::Numeric,
+                    &'static str,
+                )] = &[#(#descriptions),*];
+
                 fn bits(self) -> <Self as ::enumflags2::_internal::RawBitFlags>::Numeric {
                     self as #repr
                 }
             }
 
-            impl ::enumflags2::BitFlag for #ident {}
+            impl ::enumflags2::BitFlag for #ident {
+                const LAYOUT: &'static [(&'static str, u128)] =
+                    &[#((#layout_names, Self::#variant_names as u128)),*];
+            }
+
+            #subset_impl
+            #display_impl
+            #from_str_impl
+            #accessors_impl
+            #try_from_impl
+            #bools_struct
     })
 }
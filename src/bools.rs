@@ -0,0 +1,140 @@
+/// Implements `Serialize` and `Deserialize` for a struct generated by
+/// `#[bitflags(bools)]`, if the `serde` feature is enabled.
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __enumflags2_impl_bools_serde {
+    ($bools:ident, $flag:ident, $keys:expr) => {
+        impl $crate::_internal::serde::Serialize for $bools {
+            fn serialize<S: $crate::_internal::serde::Serializer>(
+                &self,
+                s: S,
+            ) -> $crate::_internal::core::result::Result<S::Ok, S::Error> {
+                let flags = <$crate::BitFlags<$flag> as $crate::_internal::core::convert::From<
+                    Self,
+                >>::from(*self);
+                $crate::_internal::serialize_bools(flags, $keys, stringify!($bools), s)
+            }
+        }
+
+        impl<'a> $crate::_internal::serde::Deserialize<'a> for $bools {
+            fn deserialize<D: $crate::_internal::serde::Deserializer<'a>>(
+                d: D,
+            ) -> $crate::_internal::core::result::Result<Self, D::Error> {
+                let flags = $crate::_internal::deserialize_bools::<$flag, D>($keys, d)?;
+                $crate::_internal::core::result::Result::Ok(
+                    <Self as $crate::_internal::core::convert::From<$crate::BitFlags<$flag>>>::from(
+                        flags,
+                    ),
+                )
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __enumflags2_impl_bools_serde {
+    ($bools:ident, $flag:ident, $keys:expr) => {};
+}
+
+#[cfg(feature = "serde")]
+pub use self::impl_serde::{deserialize_bools, serialize_bools};
+
+#[cfg(feature = "serde")]
+mod impl_serde {
+    use crate::formatting::flag_from_name;
+    use crate::{BitFlag, BitFlags};
+    use core::fmt;
+    use serde::de::{DeserializeSeed, Deserializer, Error, MapAccess, Unexpected, Visitor};
+    use serde::ser::{SerializeStruct, Serializer};
+
+    /// Serializes the flags as a struct with a `bool` field per flag,
+    /// named with the given keys.
+    pub fn serialize_bools<T, S>(
+        flags: BitFlags<T>,
+        keys: &'static [(T, &'static str)],
+        name: &'static str,
+        s: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        T: BitFlag,
+        S: Serializer,
+    {
+        let mut st = s.serialize_struct(name, keys.len())?;
+        for &(flag, key) in keys {
+            st.serialize_field(key, &flags.contains(flag))?;
+        }
+        st.end()
+    }
+
+    /// Deserializes the flags from a map from keys to `bool`s. The names
+    /// and aliases of the flags are accepted as keys too. Flags that are
+    /// missing are not set.
+    pub fn deserialize_bools<'a, T, D>(
+        keys: &'static [(T, &'static str)],
+        d: D,
+    ) -> Result<BitFlags<T>, D::Error>
+    where
+        T: BitFlag,
+        D: Deserializer<'a>,
+    {
+        d.deserialize_map(BoolsVisitor { keys })
+    }
+
+    struct BoolsVisitor<T: 'static> {
+        keys: &'static [(T, &'static str)],
+    }
+
+    impl<'a, T: BitFlag> Visitor<'a> for BoolsVisitor<T> {
+        type Value = BitFlags<T>;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt.write_str("a map from flag names to booleans")
+        }
+
+        fn visit_map<A: MapAccess<'a>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut flags = BitFlags::empty();
+            let mut seen = BitFlags::<T>::empty();
+            while let Some(flag) = map.next_key_seed(KeySeed { keys: self.keys })? {
+                if seen.contains(flag) {
+                    let key = self.keys.iter().find(|&&(other, _)| other.bits() == flag.bits());
+                    return Err(Error::duplicate_field(key.map_or("", |&(_, key)| key)));
+                }
+                seen |= flag;
+                flags.set(flag, map.next_value()?);
+            }
+            Ok(flags)
+        }
+    }
+
+    struct KeySeed<T: 'static> {
+        keys: &'static [(T, &'static str)],
+    }
+
+    impl<'a, T: BitFlag> DeserializeSeed<'a> for KeySeed<T> {
+        type Value = T;
+
+        fn deserialize<D: Deserializer<'a>>(self, d: D) -> Result<T, D::Error> {
+            d.deserialize_str(self)
+        }
+    }
+
+    impl<'a, T: BitFlag> Visitor<'a> for KeySeed<T> {
+        type Value = T;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt.write_str("a flag name")
+        }
+
+        fn visit_str<E: Error>(self, key: &str) -> Result<T, E> {
+            self.keys
+                .iter()
+                .find(|&&(_, other)| other == key)
+                .map(|&(flag, _)| flag)
+                .or_else(|| flag_from_name(key))
+                .ok_or_else(|| E::invalid_value(Unexpected::Str(key), &self))
+        }
+    }
+}
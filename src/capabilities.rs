@@ -0,0 +1,201 @@
+use crate::{BitFlag, BitFlags, Iter, MissingFlags};
+use core::ops;
+
+/// A set of flags that can only shrink.
+///
+/// `Capabilities<T>` is meant for passing permissions down a chain of
+/// components: each component can drop some of the capabilities it was
+/// given before passing them on, but it has no way to add any. The only
+/// way to create capabilities from a raw `BitFlags<T>` is the explicit
+/// [`Capabilities::grant`] constructor, which makes every place where
+/// capabilities are created easy to audit.
+///
+/// ```
+/// # use enumflags2::{bitflags, Capabilities};
+/// #[bitflags]
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// #[repr(u8)]
+/// enum Cap {
+///     Read = 1 << 0,
+///     Write = 1 << 1,
+///     Network = 1 << 2,
+/// }
+///
+/// fn sandboxed(caps: Capabilities<Cap>) -> Capabilities<Cap> {
+///     caps.restrict_to(Cap::Read | Cap::Network)
+/// }
+///
+/// let caps = Capabilities::grant(Cap::Read | Cap::Write);
+/// let caps = sandboxed(caps);
+/// assert!(caps.contains(Cap::Read));
+/// assert!(!caps.contains(Cap::Network));
+/// assert!(caps.require(Cap::Write).is_err());
+/// ```
+///
+/// There is no way to add flags to existing capabilities:
+///
+/// ```compile_fail
+/// # use enumflags2::{bitflags, Capabilities};
+/// # #[bitflags]
+/// # #[derive(Clone, Copy, Debug, PartialEq)]
+/// # #[repr(u8)]
+/// # enum Cap {
+/// #     Read = 1 << 0,
+/// #     Write = 1 << 1,
+/// # }
+/// let mut caps = Capabilities::grant(Cap::Read);
+/// caps.insert(Cap::Write);
+/// ```
+///
+/// ```compile_fail
+/// # use enumflags2::{bitflags, Capabilities};
+/// # #[bitflags]
+/// # #[derive(Clone, Copy, Debug, PartialEq)]
+/// # #[repr(u8)]
+/// # enum Cap {
+/// #     Read = 1 << 0,
+/// #     Write = 1 << 1,
+/// # }
+/// let caps = Capabilities::grant(Cap::Read);
+/// let caps: Capabilities<Cap> = caps | Cap::Write;
+/// ```
+///
+/// ```compile_fail
+/// # use enumflags2::{bitflags, Capabilities};
+/// # #[bitflags]
+/// # #[derive(Clone, Copy, Debug, PartialEq)]
+/// # #[repr(u8)]
+/// # enum Cap {
+/// #     Read = 1 << 0,
+/// #     Write = 1 << 1,
+/// # }
+/// let caps = Capabilities::grant(Cap::Read);
+/// let caps: Capabilities<Cap> = !caps;
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Capabilities<T: BitFlag> {
+    flags: BitFlags<T>,
+}
+
+impl<T> Capabilities<T>
+where
+    T: BitFlag,
+{
+    /// Grant the given set of capabilities.
+    ///
+    /// This is the only way to turn a `BitFlags<T>` into capabilities.
+    #[inline]
+    pub fn grant<B: Into<BitFlags<T>>>(flags: B) -> Self {
+        Capabilities {
+            flags: flags.into(),
+        }
+    }
+
+    /// An empty set of capabilities.
+    #[inline]
+    pub fn none() -> Self {
+        Capabilities {
+            flags: BitFlags::empty(),
+        }
+    }
+
+    /// Drop the given capabilities.
+    #[inline]
+    pub fn remove<B: Into<BitFlags<T>>>(&mut self, other: B) {
+        self.flags.remove(other);
+    }
+
+    /// Keep only the capabilities that are also present in `other`.
+    #[inline]
+    pub fn intersect(self, other: Self) -> Self {
+        Capabilities {
+            flags: self.flags & other.flags,
+        }
+    }
+
+    /// Keep only the capabilities that are in `allowed`.
+    ///
+    /// Unlike [`grant`][Self::grant], this can't add any capabilities,
+    /// so `allowed` can be an arbitrary `BitFlags<T>`.
+    #[inline]
+    pub fn restrict_to<B: Into<BitFlags<T>>>(self, allowed: B) -> Self {
+        Capabilities {
+            flags: self.flags & allowed.into(),
+        }
+    }
+
+    /// Return the capabilities as a plain `BitFlags<T>`.
+    #[inline]
+    pub fn flags(self) -> BitFlags<T> {
+        self.flags
+    }
+
+    /// Returns true if all of the given capabilities are present.
+    #[inline]
+    pub fn contains<B: Into<BitFlags<T>>>(self, other: B) -> bool {
+        self.flags.contains(other)
+    }
+
+    /// Returns true if at least one of the given capabilities is present.
+    #[inline]
+    pub fn intersects<B: Into<BitFlags<T>>>(self, other: B) -> bool {
+        self.flags.intersects(other)
+    }
+
+    /// Check that all the needed capabilities are present, reporting the
+    /// missing ones otherwise.
+    #[inline]
+    pub fn require<B: Into<BitFlags<T>>>(self, needed: B) -> Result<(), MissingFlags<T>> {
+        self.flags.require(needed)
+    }
+
+    /// Returns true if there are no capabilities left.
+    #[inline]
+    pub fn is_empty(self) -> bool {
+        self.flags.is_empty()
+    }
+
+    /// Returns the number of capabilities.
+    #[inline]
+    pub fn len(self) -> usize {
+        self.flags.len()
+    }
+
+    /// Iterate over the individual capabilities.
+    #[inline]
+    pub fn iter(self) -> Iter<T> {
+        self.flags.iter()
+    }
+}
+
+impl<T: BitFlag> From<Capabilities<T>> for BitFlags<T> {
+    #[inline(always)]
+    fn from(caps: Capabilities<T>) -> Self {
+        caps.flags
+    }
+}
+
+impl<T: BitFlag> ops::BitAnd for Capabilities<T> {
+    type Output = Self;
+    #[inline(always)]
+    fn bitand(self, other: Self) -> Self {
+        self.intersect(other)
+    }
+}
+
+impl<T: BitFlag> ops::BitAndAssign for Capabilities<T> {
+    #[inline(always)]
+    fn bitand_assign(&mut self, other: Self) {
+        *self = self.intersect(other);
+    }
+}
+
+impl<T: BitFlag> IntoIterator for Capabilities<T> {
+    type IntoIter = Iter<T>;
+    type Item = T;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
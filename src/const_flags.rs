@@ -0,0 +1,193 @@
+use crate::{BitFlag, BitFlagNum, BitFlags};
+use core::fmt;
+use core::marker::PhantomData;
+
+/// A set of flags that is part of the type, rather than a runtime value.
+///
+/// `ConstFlags<T, BITS>` is a zero-sized type. Functions can require
+/// some flags to be present in their argument's type, which turns a missing
+/// flag into a compile-time error. This makes it possible to encode
+/// typestate, such as the features negotiated by a protocol.
+///
+/// The `BITS` parameter is most easily written with [`make_bitflags!`](crate::make_bitflags):
+///
+/// ```
+/// # use enumflags2::{bitflags, make_bitflags, ConstFlags};
+/// #[bitflags]
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// #[repr(u8)]
+/// enum Feature {
+///     Tls = 1 << 0,
+///     Auth = 1 << 1,
+/// }
+///
+/// const TLS: u128 = make_bitflags!(Feature::Tls as u128);
+/// const AUTH: u128 = make_bitflags!(Feature::Auth as u128);
+///
+/// struct Connection<const FEATURES: u128> {
+///     features: ConstFlags<Feature, FEATURES>,
+/// }
+///
+/// impl<const FEATURES: u128> Connection<FEATURES> {
+///     fn start_tls<const OUT: u128>(self) -> Connection<OUT> {
+///         Connection { features: self.features.insert::<TLS, OUT>() }
+///     }
+///
+///     fn send_password(&self) {
+///         self.features.require::<TLS>();
+///     }
+/// }
+///
+/// let plain = Connection { features: ConstFlags::<Feature, 0>::new() };
+/// let secure: Connection<TLS> = plain.start_tls();
+/// secure.send_password();
+/// assert_eq!(secure.features.flags(), Feature::Tls);
+/// ```
+///
+/// Calling `send_password` on the plain connection fails to compile:
+///
+/// ```compile_fail
+/// # use enumflags2::{bitflags, make_bitflags, ConstFlags};
+/// # #[bitflags]
+/// # #[derive(Clone, Copy, Debug, PartialEq)]
+/// # #[repr(u8)]
+/// # enum Feature {
+/// #     Tls = 1 << 0,
+/// #     Auth = 1 << 1,
+/// # }
+/// # const TLS: u128 = make_bitflags!(Feature::Tls as u128);
+/// # struct Connection<const FEATURES: u128> {
+/// #     features: ConstFlags<Feature, FEATURES>,
+/// # }
+/// # impl<const FEATURES: u128> Connection<FEATURES> {
+/// #     fn send_password(&self) {
+/// #         self.features.require::<TLS>();
+/// #     }
+/// # }
+/// let plain = Connection { features: ConstFlags::<Feature, 0>::new() };
+/// plain.send_password();
+/// ```
+///
+/// Since stable Rust can't compute `BITS | FLAGS` at the type level,
+/// [`insert`][ConstFlags::insert] and [`remove`][ConstFlags::remove] take
+/// the resulting set as a parameter. It can usually be inferred, and
+/// is checked at compile time.
+pub struct ConstFlags<T, const BITS: u128> {
+    marker: PhantomData<T>,
+}
+
+impl<T, const BITS: u128> ConstFlags<T, BITS> {
+    /// The bits of the flags in the set.
+    pub const BITS: u128 = BITS;
+
+    /// Returns true if all of the flags in `FLAGS` are in the set.
+    #[inline(always)]
+    pub const fn contains<const FLAGS: u128>(self) -> bool {
+        BITS & FLAGS == FLAGS
+    }
+}
+
+impl<T, const BITS: u128> ConstFlags<T, BITS>
+where
+    T: BitFlag,
+{
+    /// Create the set. Fails to compile if `BITS` has bits that don't
+    /// correspond to flags of `T`.
+    #[inline(always)]
+    pub fn new() -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Valid::<T, BITS>::CHECK;
+        ConstFlags {
+            marker: PhantomData,
+        }
+    }
+
+    /// Return the flags as a runtime value.
+    #[inline(always)]
+    pub fn flags(self) -> BitFlags<T> {
+        BitFlags::from_bits_truncate(T::Numeric::truncate_from_u128(BITS))
+    }
+
+    /// Fail to compile if the set doesn't contain all of `FLAGS`.
+    #[inline(always)]
+    pub fn require<const FLAGS: u128>(&self) {
+        #[allow(clippy::let_unit_value)]
+        let () = Subset::<FLAGS, BITS>::CHECK;
+    }
+
+    /// Add `FLAGS` to the set. Fails to compile unless `OUT` is
+    /// `BITS | FLAGS`.
+    #[inline(always)]
+    pub fn insert<const FLAGS: u128, const OUT: u128>(self) -> ConstFlags<T, OUT> {
+        #[allow(clippy::let_unit_value)]
+        let () = Union::<BITS, FLAGS, OUT>::CHECK;
+        ConstFlags::new()
+    }
+
+    /// Remove `FLAGS` from the set. Fails to compile unless `OUT` is
+    /// `BITS & !FLAGS`.
+    #[inline(always)]
+    pub fn remove<const FLAGS: u128, const OUT: u128>(self) -> ConstFlags<T, OUT> {
+        #[allow(clippy::let_unit_value)]
+        let () = Difference::<BITS, FLAGS, OUT>::CHECK;
+        ConstFlags::new()
+    }
+}
+
+// Compile-time assertions. Evaluating `CHECK` fails with an out of bounds
+// index when the condition doesn't hold.
+struct Subset<const SUBSET: u128, const SET: u128>;
+
+impl<const SUBSET: u128, const SET: u128> Subset<SUBSET, SET> {
+    const CHECK: () = [()][(SUBSET & !SET != 0) as usize];
+}
+
+struct Valid<T, const BITS: u128>(PhantomData<T>);
+
+impl<T: BitFlag, const BITS: u128> Valid<T, BITS> {
+    const CHECK: () = [()][(BITS & !T::ALL_BITS_U128 != 0) as usize];
+}
+
+struct Union<const A: u128, const B: u128, const OUT: u128>;
+
+impl<const A: u128, const B: u128, const OUT: u128> Union<A, B, OUT> {
+    const CHECK: () = [()][(A | B != OUT) as usize];
+}
+
+struct Difference<const A: u128, const B: u128, const OUT: u128>;
+
+impl<const A: u128, const B: u128, const OUT: u128> Difference<A, B, OUT> {
+    const CHECK: () = [()][(A & !B != OUT) as usize];
+}
+
+impl<T, const BITS: u128> Copy for ConstFlags<T, BITS> {}
+
+impl<T, const BITS: u128> Clone for ConstFlags<T, BITS> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: BitFlag, const BITS: u128> Default for ConstFlags<T, BITS> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: BitFlag, const BITS: u128> From<ConstFlags<T, BITS>> for BitFlags<T> {
+    #[inline(always)]
+    fn from(flags: ConstFlags<T, BITS>) -> Self {
+        flags.flags()
+    }
+}
+
+impl<T, const BITS: u128> fmt::Debug for ConstFlags<T, BITS>
+where
+    T: BitFlag,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_tuple("ConstFlags").field(&self.flags()).finish()
+    }
+}
@@ -0,0 +1,89 @@
+use crate::{BitFlag, BitFlags};
+use core::fmt::{self, Write};
+
+/// A multi-line summary of a set of flags, with their labels and
+/// descriptions, returned by [`BitFlags::explain`].
+///
+/// Each set flag gets a line with its [label][BitFlag::label], followed by
+/// its [description][BitFlag::description], if any. The descriptions are
+/// aligned, and descriptions that span multiple lines are indented.
+/// An empty set is displayed as an empty string.
+///
+/// ```
+/// # use enumflags2::{bitflags, BitFlags};
+/// #[bitflags]
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// #[repr(u8)]
+/// enum Perm {
+///     /// Allows reading the file.
+///     Read = 1 << 0,
+///     /// Allows running the file
+///     /// as a program.
+///     #[bitflag(label = "Exec")]
+///     Execute = 1 << 1,
+///     Sticky = 1 << 2,
+/// }
+///
+/// let perms = Perm::Read | Perm::Execute | Perm::Sticky;
+/// assert_eq!(
+///     perms.explain().to_string(),
+///     "Read    Allows reading the file.\n\
+///      Exec    Allows running the file\n        as a program.\n\
+///      Sticky",
+/// );
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct ExplainFlags<T: BitFlag> {
+    flags: BitFlags<T>,
+}
+
+impl<T> BitFlags<T>
+where
+    T: BitFlag,
+{
+    /// Describe the flags that are set, one per line. See [`ExplainFlags`].
+    pub fn explain(self) -> ExplainFlags<T> {
+        ExplainFlags { flags: self }
+    }
+}
+
+impl<T: BitFlag> fmt::Display for ExplainFlags<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .flags
+            .iter()
+            .map(|flag| flag.label().chars().count())
+            .max()
+            .unwrap_or(0);
+
+        for (i, flag) in self.flags.iter().enumerate() {
+            if i != 0 {
+                fmt.write_char('\n')?;
+            }
+
+            let label = flag.label();
+            fmt.write_str(label)?;
+            let description = match flag.description() {
+                Some(description) => description,
+                None => continue,
+            };
+
+            let padding = width - label.chars().count() + 2;
+            for (i, line) in description.lines().enumerate() {
+                if i != 0 {
+                    fmt.write_char('\n')?;
+                }
+
+                let indent = if i == 0 { padding } else { width + 2 };
+                if !line.is_empty() {
+                    for _ in 0..indent {
+                        fmt.write_char(' ')?;
+                    }
+                    fmt.write_str(line)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,5 +1,7 @@
 use super::BitFlag;
 use super::BitFlags;
+use crate::formatting::VariantName;
+use crate::BitFlagNum;
 use core::convert::TryFrom;
 use core::fmt;
 
@@ -21,8 +23,9 @@ for_each_uint! { $ty $hide_docs =>
 /// The error struct used by [`BitFlags::from_bits`]
 /// and the [`TryFrom`] implementation for invalid values.
 ///
-/// Note that the implementation of [`std::error::Error`]
-/// for this type is gated on the `std` feature flag.
+/// The error type implements [`core::error::Error`] on Rust 1.81 and newer.
+/// On older compilers, the implementation of [`std::error::Error`]
+/// is gated on the `std` feature flag.
 ///
 /// ```
 /// # use std::convert::TryInto;
@@ -41,12 +44,18 @@ for_each_uint! { $ty $hide_docs =>
 /// assert!(result.is_err());
 /// let error = result.unwrap_err();
 /// assert_eq!(error.truncate(), MyFlags::C | MyFlags::A);
+/// assert_eq!(error.valid_bits(), 0b00101);
 /// assert_eq!(error.invalid_bits(), 0b10000);
+/// assert_eq!(
+///     error.to_string(),
+///     "Invalid bits for BitFlags<MyFlags>(0b101, A | C): bit 4",
+/// );
 /// ```
 #[derive(Debug, Copy, Clone)]
 pub struct FromBitsError<T: BitFlag> {
     pub(crate) flags: BitFlags<T>,
     pub(crate) invalid: T::Numeric,
+    pub(crate) legacy: Option<u32>,
 }
 
 impl<T: BitFlag> FromBitsError<T> {
@@ -55,25 +64,241 @@ impl<T: BitFlag> FromBitsError<T> {
         self.flags
     }
 
+    /// Return the bits that correspond to flags. Together with
+    /// [`invalid_bits`][Self::invalid_bits], they make up the original value.
+    pub fn valid_bits(self) -> T::Numeric {
+        self.flags.bits()
+    }
+
     /// Return the bits that didn't correspond to any flags.
     pub fn invalid_bits(self) -> T::Numeric {
         self.invalid
     }
+
+    /// If the value isn't valid with the current values of the flags, but is
+    /// valid with the values they had [before some version](index.html#versioned-values),
+    /// return that version. The value was probably written by an older
+    /// version of the program.
+    ///
+    /// If several older layouts match, the most recent one is returned.
+    pub fn legacy_layout(self) -> Option<u32> {
+        self.legacy
+    }
+
+    /// Turn the error into another type, given the truncated result
+    /// and the invalid bits.
+    ///
+    /// ```
+    /// # use enumflags2::{bitflags, BitFlags};
+    /// # #[bitflags]
+    /// # #[derive(Clone, Copy, Debug)]
+    /// # #[repr(u8)]
+    /// # enum MyFlags {
+    /// #     A = 0b0001,
+    /// #     B = 0b0010,
+    /// # }
+    /// #[derive(Debug, PartialEq)]
+    /// enum ConfigError {
+    ///     UnknownOptions(u8),
+    /// }
+    ///
+    /// let result = BitFlags::<MyFlags>::from_bits(0b1001)
+    ///     .map_err(|err| err.map(|_, invalid| ConfigError::UnknownOptions(invalid)));
+    /// assert_eq!(result.unwrap_err(), ConfigError::UnknownOptions(0b1000));
+    /// ```
+    pub fn map<E, F>(self, f: F) -> E
+    where
+        F: FnOnce(BitFlags<T>, T::Numeric) -> E,
+    {
+        f(self.flags, self.invalid)
+    }
 }
 
-impl<T: BitFlag + fmt::Debug> fmt::Display for FromBitsError<T> {
+impl<T: BitFlag> fmt::Display for FromBitsError<T> {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            fmt,
-            "Invalid bits for {:?}: {:#b}",
-            self.flags, self.invalid
-        )
+        write!(fmt, "Invalid bits for {:?}: ", self.flags)?;
+
+        let mut rest = self.invalid;
+        let mut separator = "";
+        while rest != T::EMPTY {
+            let bit = rest & rest.wrapping_neg();
+            rest = rest & !bit;
+
+            write!(fmt, "{}bit {}", separator, bit.trailing_zeros())?;
+            separator = ", ";
+            if let Some((_, name)) = T::RESERVED_BITS.iter().find(|(mask, _)| *mask & bit != T::EMPTY) {
+                write!(fmt, " ({})", name)?;
+            } else if let Some(&(current, _, until)) =
+                T::HISTORY.iter().find(|&&(_, previous, _)| previous == bit)
+            {
+                if let Some(flag) = BitFlags::<T>::from_bits_truncate(current).iter().next() {
+                    write!(fmt, " ({:?} before version {})", VariantName(flag), until)?;
+                }
+            }
+        }
+
+        if let Some(version) = self.legacy {
+            write!(fmt, "; valid in the layout used before version {}", version)?;
+        }
+
+        Ok(())
     }
 }
 
-#[cfg(feature = "std")]
-impl<T: BitFlag + fmt::Debug> std::error::Error for FromBitsError<T> {
-    fn description(&self) -> &str {
-        "invalid bitflags representation"
+#[cfg(enumflags2_core_error)]
+impl<T: BitFlag + fmt::Debug> core::error::Error for FromBitsError<T> {}
+
+#[cfg(all(feature = "std", not(enumflags2_core_error)))]
+impl<T: BitFlag + fmt::Debug> std::error::Error for FromBitsError<T> {}
+
+impl<T> BitFlags<T>
+where
+    T: BitFlag,
+{
+    /// Check that all the flags in `needed` are set. If some of them aren't,
+    /// the error reports which ones.
+    ///
+    /// ```
+    /// # use enumflags2::{bitflags, BitFlags};
+    /// #[bitflags]
+    /// #[derive(Clone, Copy, Debug, PartialEq)]
+    /// #[repr(u8)]
+    /// enum Perm {
+    ///     Read = 1 << 0,
+    ///     Write = 1 << 1,
+    ///     Admin = 1 << 2,
+    /// }
+    ///
+    /// let granted = BitFlags::from(Perm::Read);
+    /// assert!(granted.require(Perm::Read).is_ok());
+    ///
+    /// let error = granted.require(Perm::Read | Perm::Write | Perm::Admin).unwrap_err();
+    /// assert_eq!(error.missing(), Perm::Write | Perm::Admin);
+    /// assert_eq!(error.to_string(), "missing flags: Write | Admin");
+    /// ```
+    #[inline]
+    pub fn require<B: Into<BitFlags<T>>>(self, needed: B) -> Result<(), MissingFlags<T>> {
+        let missing = needed.into() & !self;
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(MissingFlags { missing })
+        }
+    }
+
+    /// Check that no flags outside of `allowed` are set. If some are,
+    /// the error reports which ones.
+    ///
+    /// ```
+    /// # use enumflags2::{bitflags, BitFlags, FromBitsError};
+    /// #[bitflags]
+    /// #[derive(Clone, Copy, Debug, PartialEq)]
+    /// #[repr(u8)]
+    /// enum Perm {
+    ///     Read = 1 << 0,
+    ///     Write = 1 << 1,
+    ///     Admin = 1 << 2,
+    /// }
+    ///
+    /// let requested = Perm::Read | Perm::Admin;
+    /// let error = requested.ensure_only(Perm::Read | Perm::Write).unwrap_err();
+    /// assert_eq!(error.unexpected(), Perm::Admin);
+    /// assert_eq!(error.truncate(), Perm::Read);
+    /// assert_eq!(error.to_string(), "unexpected flags: Admin");
+    ///
+    /// // The unexpected flags can be reported like invalid bits.
+    /// let error = FromBitsError::from(error);
+    /// assert_eq!(error.invalid_bits(), 0b100);
+    /// ```
+    #[inline]
+    pub fn ensure_only<B: Into<BitFlags<T>>>(self, allowed: B) -> Result<(), UnexpectedFlags<T>> {
+        let allowed = allowed.into();
+        let unexpected = self & !allowed;
+        if unexpected.is_empty() {
+            Ok(())
+        } else {
+            Err(UnexpectedFlags {
+                flags: self & allowed,
+                unexpected,
+            })
+        }
+    }
+}
+
+/// The error returned by [`BitFlags::require`] when some of the needed
+/// flags aren't set.
+///
+/// Like [`FromBitsError`], this type implements [`core::error::Error`]
+/// on Rust 1.81 and newer, or [`std::error::Error`] with the `std` feature.
+#[derive(Debug, Copy, Clone)]
+pub struct MissingFlags<T: BitFlag> {
+    missing: BitFlags<T>,
+}
+
+impl<T: BitFlag> MissingFlags<T> {
+    /// Return the flags that were needed, but not set.
+    pub fn missing(self) -> BitFlags<T> {
+        self.missing
+    }
+}
+
+impl<T: BitFlag> fmt::Display for MissingFlags<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "missing flags: {}", self.missing)
+    }
+}
+
+#[cfg(enumflags2_core_error)]
+impl<T: BitFlag + fmt::Debug> core::error::Error for MissingFlags<T> {}
+
+#[cfg(all(feature = "std", not(enumflags2_core_error)))]
+impl<T: BitFlag + fmt::Debug> std::error::Error for MissingFlags<T> {}
+
+/// The error returned by [`BitFlags::ensure_only`] when some flags outside
+/// of the allowed set are set.
+///
+/// It can be converted into a [`FromBitsError`], which reports the
+/// unexpected flags as invalid bits.
+///
+/// Like [`FromBitsError`], this type implements [`core::error::Error`]
+/// on Rust 1.81 and newer, or [`std::error::Error`] with the `std` feature.
+#[derive(Debug, Copy, Clone)]
+pub struct UnexpectedFlags<T: BitFlag> {
+    flags: BitFlags<T>,
+    unexpected: BitFlags<T>,
+}
+
+impl<T: BitFlag> UnexpectedFlags<T> {
+    /// Return the flags that were set, but not allowed.
+    pub fn unexpected(self) -> BitFlags<T> {
+        self.unexpected
+    }
+
+    /// Return the flags that were set and allowed.
+    pub fn truncate(self) -> BitFlags<T> {
+        self.flags
+    }
+}
+
+impl<T: BitFlag> fmt::Display for UnexpectedFlags<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "unexpected flags: {}", self.unexpected)
+    }
+}
+
+#[cfg(enumflags2_core_error)]
+impl<T: BitFlag + fmt::Debug> core::error::Error for UnexpectedFlags<T> {}
+
+#[cfg(all(feature = "std", not(enumflags2_core_error)))]
+impl<T: BitFlag + fmt::Debug> std::error::Error for UnexpectedFlags<T> {}
+
+impl<T: BitFlag> From<UnexpectedFlags<T>> for FromBitsError<T> {
+    #[inline]
+    fn from(error: UnexpectedFlags<T>) -> Self {
+        FromBitsError {
+            flags: error.flags,
+            invalid: error.unexpected.bits(),
+            legacy: None,
+        }
     }
 }
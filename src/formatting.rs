@@ -1,15 +1,16 @@
+use crate::_internal::BitFlagNum;
 use crate::{BitFlag, BitFlags};
-use core::fmt::{self, Binary, Debug};
+use core::fmt::{self, Binary, Debug, Write};
 
 impl<T> fmt::Debug for BitFlags<T>
 where
-    T: BitFlag + fmt::Debug,
+    T: BitFlag,
 {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         let name = T::BITFLAGS_TYPE_NAME;
         let bits = DebugBinaryFormatter(&self.val);
         let iter = if !self.is_empty() {
-            Some(FlagFormatter(self.iter()))
+            Some(FlagFormatter(self.iter().map(VariantName)))
         } else {
             None
         };
@@ -36,10 +37,240 @@ where
 
 impl<T> fmt::Display for BitFlags<T>
 where
-    T: BitFlag + fmt::Debug,
+    T: BitFlag,
 {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::Debug::fmt(&FlagFormatter(self.iter()), fmt)
+        fmt::Debug::fmt(&FlagFormatter(self.iter().map(FlagName)), fmt)
+    }
+}
+
+/// A configurable [`Display`][fmt::Display] implementation for
+/// [`BitFlags`], returned by [`BitFlags::display`].
+///
+/// By default, the output is the same as that of `Display for BitFlags<T>`.
+/// Formatting doesn't allocate, and the width, fill and alignment of the
+/// format string are applied to the whole output.
+///
+/// ```
+/// # use enumflags2::{bitflags, BitFlags};
+/// #[bitflags]
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// #[repr(u8)]
+/// enum Perm {
+///     Read = 1 << 0,
+///     Write = 1 << 1,
+///     Execute = 1 << 2,
+/// }
+///
+/// let perms = Perm::Read | Perm::Execute;
+/// assert_eq!(perms.display().to_string(), "Read | Execute");
+/// assert_eq!(perms.display().separator("+").lowercase().to_string(), "read+execute");
+/// assert_eq!(perms.display().braces().hex().to_string(), "{Read, Execute} (0x5)");
+/// assert_eq!(BitFlags::<Perm>::empty().display().braces().to_string(), "{}");
+/// assert_eq!(format!("[{:>24}]", perms.display().binary()), "[  Read | Execute (0b101)]");
+/// ```
+#[derive(Copy, Clone)]
+pub struct DisplayFlags<'a, T: BitFlag> {
+    flags: BitFlags<T>,
+    separator: &'a str,
+    empty: &'a str,
+    braces: bool,
+    case: Case,
+    value: Option<Radix>,
+}
+
+#[derive(Copy, Clone)]
+enum Case {
+    Unchanged,
+    Lower,
+    Upper,
+}
+
+#[derive(Copy, Clone)]
+enum Radix {
+    Binary,
+    Hex,
+}
+
+impl<T> BitFlags<T>
+where
+    T: BitFlag,
+{
+    /// Configure how the flags are displayed. See [`DisplayFlags`].
+    pub fn display(self) -> DisplayFlags<'static, T> {
+        DisplayFlags {
+            flags: self,
+            separator: " | ",
+            empty: "<empty>",
+            braces: false,
+            case: Case::Unchanged,
+            value: None,
+        }
+    }
+}
+
+impl<'a, T: BitFlag> DisplayFlags<'a, T> {
+    /// Separate the flags with `separator`, instead of ` | `.
+    pub fn separator<'b>(self, separator: &'b str) -> DisplayFlags<'b, T>
+    where
+        'a: 'b,
+    {
+        DisplayFlags { separator, ..self }
+    }
+
+    /// Show `empty` when no flags are set, instead of `<empty>`.
+    pub fn empty<'b>(self, empty: &'b str) -> DisplayFlags<'b, T>
+    where
+        'a: 'b,
+    {
+        DisplayFlags { empty, ..self }
+    }
+
+    /// Display the flags like a set, such as `{A, B}`. The separator
+    /// becomes `, `, and no flags are displayed as `{}`. Both can still be
+    /// changed afterwards.
+    pub fn braces(self) -> Self {
+        DisplayFlags {
+            separator: ", ",
+            empty: "",
+            braces: true,
+            ..self
+        }
+    }
+
+    /// Write the flag names in lowercase.
+    pub fn lowercase(self) -> Self {
+        DisplayFlags {
+            case: Case::Lower,
+            ..self
+        }
+    }
+
+    /// Write the flag names in uppercase.
+    pub fn uppercase(self) -> Self {
+        DisplayFlags {
+            case: Case::Upper,
+            ..self
+        }
+    }
+
+    /// Follow the flags with their numeric value in hexadecimal,
+    /// such as `A | C (0x5)`.
+    pub fn hex(self) -> Self {
+        DisplayFlags {
+            value: Some(Radix::Hex),
+            ..self
+        }
+    }
+
+    /// Follow the flags with their numeric value in binary,
+    /// such as `A | C (0b101)`.
+    pub fn binary(self) -> Self {
+        DisplayFlags {
+            value: Some(Radix::Binary),
+            ..self
+        }
+    }
+
+    fn write_to<W: Write>(&self, out: &mut W) -> fmt::Result {
+        if self.braces {
+            out.write_char('{')?;
+        }
+
+        if self.flags.is_empty() {
+            out.write_str(self.empty)?;
+        }
+
+        for (i, flag) in self.flags.iter().enumerate() {
+            if i != 0 {
+                out.write_str(self.separator)?;
+            }
+
+            let mut out = CaseWriter {
+                out: &mut *out,
+                case: self.case,
+            };
+            write!(out, "{:?}", FlagName(flag))?;
+        }
+
+        if self.braces {
+            out.write_char('}')?;
+        }
+
+        let bits = self.flags.bits().to_u128();
+        match self.value {
+            Some(Radix::Hex) => write!(out, " ({:#x})", bits),
+            Some(Radix::Binary) => write!(out, " ({:#b})", bits),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T: BitFlag> fmt::Display for DisplayFlags<'_, T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = match fmt.width() {
+            Some(width) => width,
+            None => return self.write_to(fmt),
+        };
+
+        let mut counter = CharCounter(0);
+        self.write_to(&mut counter)?;
+        let padding = width.saturating_sub(counter.0);
+        let (before, after) = match fmt.align() {
+            None | Some(fmt::Alignment::Left) => (0, padding),
+            Some(fmt::Alignment::Right) => (padding, 0),
+            Some(fmt::Alignment::Center) => (padding / 2, padding - padding / 2),
+        };
+
+        let fill = fmt.fill();
+        for _ in 0..before {
+            fmt.write_char(fill)?;
+        }
+        self.write_to(fmt)?;
+        for _ in 0..after {
+            fmt.write_char(fill)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: BitFlag> fmt::Debug for DisplayFlags<'_, T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_char('"')?;
+        self.write_to(fmt)?;
+        fmt.write_char('"')
+    }
+}
+
+// Changes the case of everything written through it.
+struct CaseWriter<'w, W> {
+    out: &'w mut W,
+    case: Case,
+}
+
+impl<W: Write> Write for CaseWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        match self.case {
+            Case::Unchanged => self.out.write_str(s),
+            Case::Lower => s
+                .chars()
+                .flat_map(char::to_lowercase)
+                .try_for_each(|c| self.out.write_char(c)),
+            Case::Upper => s
+                .chars()
+                .flat_map(char::to_uppercase)
+                .try_for_each(|c| self.out.write_char(c)),
+        }
+    }
+}
+
+// Counts the characters written, to compute the padding.
+struct CharCounter(usize);
+
+impl Write for CharCounter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.chars().count();
+        Ok(())
     }
 }
 
@@ -102,6 +333,208 @@ impl<T: Debug, I: Clone + Iterator<Item = T>> Debug for FlagFormatter<I> {
     }
 }
 
+/// Formats a flag using the name of its variant.
+#[derive(Clone, Copy)]
+pub(crate) struct VariantName<T>(pub(crate) T);
+
+impl<T: BitFlag> Debug for VariantName<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bits = self.0.bits().to_u128();
+        match T::LAYOUT.iter().find(|&&(_, flag)| flag == bits) {
+            Some((name, _)) => fmt.write_str(name),
+            None => write!(fmt, "{:#x}", bits),
+        }
+    }
+}
+
+/// Formats a flag using its canonical name, as set with
+/// `#[bitflag(rename = "...")]` or `#[bitflags(rename_all = "...")]`.
+/// Falls back to the name of the variant for types without a name table.
+#[derive(Clone, Copy)]
+pub(crate) struct FlagName<T>(pub(crate) T);
+
+impl<T: BitFlag> Debug for FlagName<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bits = self.0.bits();
+        match T::NAMES.iter().find(|&&(flag, _)| flag == bits) {
+            Some((_, name)) => fmt.write_str(name),
+            None => Debug::fmt(&VariantName(self.0), fmt),
+        }
+    }
+}
+
+/// Find the flag with the given name. Both canonical names and aliases are
+/// accepted. For types without a name table, the name of the variant
+/// has to match exactly.
+pub(crate) fn flag_from_name<T: BitFlag>(name: &str) -> Option<T> {
+    if T::NAMES.is_empty() {
+        return BitFlags::<T>::all()
+            .iter()
+            .find(|flag| T::LAYOUT.contains(&(name, flag.bits().to_u128())));
+    }
+
+    T::NAMES
+        .iter()
+        .chain(T::ALIASES)
+        .find(|&&(_, candidate)| candidate == name)
+        .and_then(|&(bits, _)| BitFlags::<T>::from_bits(bits).ok())
+        .and_then(|flags| flags.exactly_one())
+}
+
+/// The error returned when parsing a flag with the `FromStr` implementation
+/// generated by `#[bitflags(from_str)]` fails.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ParseFlagError;
+
+impl fmt::Display for ParseFlagError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("unknown flag name")
+    }
+}
+
+#[cfg(enumflags2_core_error)]
+impl core::error::Error for ParseFlagError {}
+
+#[cfg(all(feature = "std", not(enumflags2_core_error)))]
+impl std::error::Error for ParseFlagError {}
+
+/// The `Display` implementation generated by `#[bitflags(display)]`.
+pub fn fmt_flag_name<T: BitFlag>(flag: T, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let bits = flag.bits();
+    let name = match T::NAMES.iter().find(|&&(flag, _)| flag == bits) {
+        Some(&(_, name)) => Some(name),
+        None => T::LAYOUT
+            .iter()
+            .find(|&&(_, flag)| flag == bits.to_u128())
+            .map(|&(name, _)| name),
+    };
+
+    match name {
+        Some(name) => fmt.pad(name),
+        None => Debug::fmt(&VariantName(flag), fmt),
+    }
+}
+
+/// The `FromStr` implementation generated by `#[bitflags(from_str)]`.
+pub fn parse_flag_name<T: BitFlag>(name: &str) -> Result<T, ParseFlagError> {
+    flag_from_name(name).ok_or(ParseFlagError)
+}
+
+#[cfg(feature = "serde")]
+pub use self::impl_serde::{deserialize_names, serialize_names};
+
+#[cfg(feature = "serde")]
+mod impl_serde {
+    use super::{flag_from_name, FlagName};
+    use crate::{BitFlag, BitFlags};
+    use core::fmt;
+    use core::marker::PhantomData;
+    use serde::de::{DeserializeSeed, Error, SeqAccess, Unexpected, Visitor};
+    use serde::ser::{Serialize, SerializeSeq, Serializer};
+    use serde::Deserializer;
+
+    /// Serializes a `BitFlags<T>` as a sequence of flag names, for use with
+    /// `#[serde(serialize_with = "enumflags2::serialize_names")]`. Each flag
+    /// is written with its canonical name.
+    pub fn serialize_names<T, S>(flags: &BitFlags<T>, s: S) -> Result<S::Ok, S::Error>
+    where
+        T: BitFlag,
+        S: Serializer,
+    {
+        let mut seq = s.serialize_seq(Some(flags.len()))?;
+        for flag in flags.iter() {
+            seq.serialize_element(&SerializeName(flag))?;
+        }
+        seq.end()
+    }
+
+    /// Deserializes a `BitFlags<T>` from a sequence of flag names, for use
+    /// with `#[serde(deserialize_with = "enumflags2::deserialize_names")]`.
+    /// Aliases are accepted as well as canonical names.
+    ///
+    /// ```
+    /// # use enumflags2::{bitflags, BitFlags};
+    /// use serde::de::value::{Error, SeqDeserializer};
+    ///
+    /// #[bitflags(rename_all = "snake_case")]
+    /// #[derive(Clone, Copy, Debug, PartialEq)]
+    /// #[repr(u8)]
+    /// enum Perm {
+    ///     Read = 1 << 0,
+    ///     #[bitflag(alias = "modify")]
+    ///     ReadWrite = 1 << 1,
+    /// }
+    ///
+    /// let names = SeqDeserializer::<_, Error>::new(["read", "modify"].iter().copied());
+    /// let perms: BitFlags<Perm> = enumflags2::deserialize_names(names).unwrap();
+    /// assert_eq!(perms, Perm::Read | Perm::ReadWrite);
+    /// ```
+    pub fn deserialize_names<'a, T, D>(d: D) -> Result<BitFlags<T>, D::Error>
+    where
+        T: BitFlag,
+        D: Deserializer<'a>,
+    {
+        d.deserialize_seq(NamesVisitor(PhantomData))
+    }
+
+    struct SerializeName<T>(T);
+
+    impl<T: BitFlag> Serialize for SerializeName<T> {
+        fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+            s.collect_str(&DisplayName(self.0))
+        }
+    }
+
+    struct DisplayName<T>(T);
+
+    impl<T: BitFlag> fmt::Display for DisplayName<T> {
+        fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt::Debug::fmt(&FlagName(self.0), fmt)
+        }
+    }
+
+    struct NamesVisitor<T>(PhantomData<T>);
+
+    impl<'a, T: BitFlag> Visitor<'a> for NamesVisitor<T> {
+        type Value = BitFlags<T>;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt.write_str("a sequence of flag names")
+        }
+
+        fn visit_seq<A: SeqAccess<'a>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut flags = BitFlags::empty();
+            while let Some(flag) = seq.next_element_seed(NameSeed::<T>(PhantomData))? {
+                flags |= flag;
+            }
+            Ok(flags)
+        }
+    }
+
+    /// Deserializes a flag from its name or one of its aliases.
+    struct NameSeed<T>(PhantomData<T>);
+
+    impl<'a, T: BitFlag> DeserializeSeed<'a> for NameSeed<T> {
+        type Value = T;
+
+        fn deserialize<D: Deserializer<'a>>(self, d: D) -> Result<T, D::Error> {
+            d.deserialize_str(self)
+        }
+    }
+
+    impl<'a, T: BitFlag> Visitor<'a> for NameSeed<T> {
+        type Value = T;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt.write_str("a flag name")
+        }
+
+        fn visit_str<E: Error>(self, name: &str) -> Result<T, E> {
+            flag_from_name(name).ok_or_else(|| E::invalid_value(Unexpected::Str(name), &self))
+        }
+    }
+}
+
 // A formatter that obeys format arguments but falls back to binary when
 // no explicit format is requested. Supports {:08?}, {:08x?}, etc.
 struct DebugBinaryFormatter<'a, F>(&'a F);
@@ -0,0 +1,294 @@
+use crate::subsets::{deposit, extract};
+use crate::{BitFlag, BitFlags, FlagPattern};
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::{fmt, ops};
+
+/// The maximum number of flags supported by [`FlagFunction`].
+pub const MAX_FUNCTION_FLAGS: u32 = 8;
+
+/// An arbitrary boolean function over `BitFlags<T>`, stored as a truth table.
+///
+/// The table has one bit for each of the `2^n` possible sets of flags,
+/// where `n` is the number of flags in `T`. This makes all the operations
+/// cheap and exact, but limits `T` to [`MAX_FUNCTION_FLAGS`] flags.
+/// Functions can be combined with [`and`][FlagFunction::and],
+/// [`or`][FlagFunction::or], and negated with `!`.
+///
+/// ```
+/// # use enumflags2::{bitflags, BitFlags, FlagFunction};
+/// #[bitflags]
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// #[repr(u8)]
+/// enum Topping {
+///     Cheese = 1 << 0,
+///     Ham = 1 << 1,
+///     Pineapple = 1 << 2,
+/// }
+///
+/// let cheese = FlagFunction::flag(Topping::Cheese);
+/// let hawaii = FlagFunction::from_fn(|t: BitFlags<Topping>| t.contains(Topping::Ham | Topping::Pineapple));
+///
+/// let allowed = cheese.and(!hawaii);
+/// assert_eq!(allowed.count(), 3);
+/// assert!(allowed.matches(Topping::Cheese | Topping::Ham));
+/// assert_eq!(allowed.satisfying().collect::<Vec<_>>(), [
+///     Topping::Cheese.into(),
+///     Topping::Cheese | Topping::Ham,
+///     Topping::Cheese | Topping::Pineapple,
+/// ]);
+/// ```
+///
+/// Using `FlagFunction` with a `T` that has more than [`MAX_FUNCTION_FLAGS`]
+/// flags fails to compile:
+///
+/// ```compile_fail
+/// # use enumflags2::{bitflags, FlagFunction};
+/// #[bitflags]
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// #[repr(u16)]
+/// enum Nine {
+///     A = 1 << 0,
+///     B = 1 << 1,
+///     C = 1 << 2,
+///     D = 1 << 3,
+///     E = 1 << 4,
+///     F = 1 << 5,
+///     G = 1 << 6,
+///     H = 1 << 7,
+///     I = 1 << 8,
+/// }
+///
+/// let _ = FlagFunction::flag(Nine::A);
+/// ```
+///
+/// # Panics
+///
+/// For a hand-written [`BitFlag`] implementation that leaves
+/// `ALL_BITS_U128` at its default, the number of flags is only known at
+/// runtime, so the constructors panic instead.
+pub struct FlagFunction<T> {
+    table: [u64; 4],
+    marker: PhantomData<T>,
+}
+
+impl<T> FlagFunction<T>
+where
+    T: BitFlag,
+{
+    // Evaluating `CHECK` fails with an out of bounds index when `T` has
+    // too many flags. The default `ALL_BITS_U128` is left to `from_fn`.
+    const CHECK: () = [()][(T::ALL_BITS_U128 != !0
+        && T::ALL_BITS_U128.count_ones() > MAX_FUNCTION_FLAGS) as usize];
+
+    /// The number of sets of flags, i.e. the number of bits used in the table.
+    #[inline]
+    fn size() -> u32 {
+        1 << BitFlags::<T>::all().len()
+    }
+
+    /// The position of a set of flags in the table.
+    #[inline]
+    fn index(flags: BitFlags<T>) -> usize {
+        extract(flags.bits(), T::ALL_BITS) as usize
+    }
+
+    /// Create a function from its value for each set of flags.
+    pub fn from_fn<F>(mut f: F) -> Self
+    where
+        F: FnMut(BitFlags<T>) -> bool,
+    {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::CHECK;
+        assert!(
+            BitFlags::<T>::all().len() as u32 <= MAX_FUNCTION_FLAGS,
+            "FlagFunction supports at most {} flags",
+            MAX_FUNCTION_FLAGS,
+        );
+
+        let mut table = [0; 4];
+        for index in 0..Self::size() {
+            // SAFETY: `deposit` only sets bits from `ALL_BITS`, which are valid.
+            let flags = unsafe { BitFlags::from_bits_unchecked(deposit(index.into(), T::ALL_BITS)) };
+            if f(flags) {
+                table[index as usize / 64] |= 1 << (index % 64);
+            }
+        }
+
+        FlagFunction {
+            table,
+            marker: PhantomData,
+        }
+    }
+
+    /// The function that is true when `flag` is set.
+    #[inline]
+    pub fn flag(flag: T) -> Self {
+        Self::from_fn(|flags| flags.contains(flag))
+    }
+
+    /// The function that always returns `value`.
+    #[inline]
+    pub fn constant(value: bool) -> Self {
+        Self::from_fn(|_| value)
+    }
+
+    /// Evaluate the function for the given set of flags.
+    #[inline]
+    pub fn matches<B: Into<BitFlags<T>>>(&self, flags: B) -> bool {
+        let index = Self::index(flags.into());
+        self.table[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    /// The function that is true when both `self` and `other` are.
+    /// Same as the `&` operator.
+    #[inline]
+    pub fn and(self, other: Self) -> Self {
+        self.zip(other, |a, b| a & b)
+    }
+
+    /// The function that is true when either `self` or `other` is.
+    /// Same as the `|` operator.
+    #[inline]
+    pub fn or(self, other: Self) -> Self {
+        self.zip(other, |a, b| a | b)
+    }
+
+    #[inline]
+    fn zip(self, other: Self, f: impl Fn(u64, u64) -> u64) -> Self {
+        let mut table = self.table;
+        for (word, other) in table.iter_mut().zip(other.table.iter()) {
+            *word = f(*word, *other);
+        }
+
+        FlagFunction {
+            table,
+            marker: PhantomData,
+        }
+    }
+
+    /// Return the number of sets of flags for which the function is true.
+    #[inline]
+    pub fn count(&self) -> usize {
+        self.table.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Iterate over the sets of flags for which the function is true,
+    /// in increasing order of their numeric value.
+    #[inline]
+    pub fn satisfying(&self) -> Satisfying<T> {
+        Satisfying {
+            table: self.table,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: BitFlag> From<FlagPattern<T>> for FlagFunction<T> {
+    #[inline]
+    fn from(pattern: FlagPattern<T>) -> Self {
+        Self::from_fn(|flags| pattern.matches(flags))
+    }
+}
+
+impl<T> Copy for FlagFunction<T> {}
+
+impl<T> Clone for FlagFunction<T> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> PartialEq for FlagFunction<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.table == other.table
+    }
+}
+
+impl<T> Eq for FlagFunction<T> {}
+
+impl<T> core::hash::Hash for FlagFunction<T> {
+    #[inline]
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.table.hash(state)
+    }
+}
+
+impl<T> fmt::Debug for FlagFunction<T>
+where
+    T: BitFlag,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_set().entries(self.satisfying()).finish()
+    }
+}
+
+impl<T: BitFlag> ops::BitAnd for FlagFunction<T> {
+    type Output = Self;
+    #[inline(always)]
+    fn bitand(self, other: Self) -> Self {
+        self.and(other)
+    }
+}
+
+impl<T: BitFlag> ops::BitOr for FlagFunction<T> {
+    type Output = Self;
+    #[inline(always)]
+    fn bitor(self, other: Self) -> Self {
+        self.or(other)
+    }
+}
+
+impl<T: BitFlag> ops::Not for FlagFunction<T> {
+    type Output = Self;
+    /// The function that is true when `self` is false.
+    #[inline]
+    fn not(self) -> Self {
+        self.zip(Self::constant(true), |a, all| !a & all)
+    }
+}
+
+/// Iterator over the sets of flags accepted by a [`FlagFunction`], created
+/// by [`FlagFunction::satisfying`].
+#[derive(Clone)]
+pub struct Satisfying<T> {
+    table: [u64; 4],
+    marker: PhantomData<T>,
+}
+
+impl<T> Iterator for Satisfying<T>
+where
+    T: BitFlag,
+{
+    type Item = BitFlags<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let word = self.table.iter().position(|&word| word != 0)?;
+        let bit = self.table[word].trailing_zeros();
+        self.table[word] &= self.table[word] - 1;
+
+        let index = word as u128 * 64 + u128::from(bit);
+        // SAFETY: `deposit` only sets bits from `ALL_BITS`, which are valid.
+        Some(unsafe { BitFlags::from_bits_unchecked(deposit(index, T::ALL_BITS)) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.table.iter().map(|word| word.count_ones() as usize).sum();
+        (len, Some(len))
+    }
+}
+
+impl<T: BitFlag> ExactSizeIterator for Satisfying<T> {}
+
+impl<T: BitFlag> FusedIterator for Satisfying<T> {}
+
+impl<T> fmt::Debug for Satisfying<T>
+where
+    T: BitFlag,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_list().entries(self.clone()).finish()
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{BitFlag, BitFlags, BitFlagNum};
-use core::iter::{FromIterator, FusedIterator};
+use core::iter::{FromIterator, FusedIterator, Sum};
 
 impl<T> BitFlags<T>
 where
@@ -26,6 +26,128 @@ where
     pub fn iter(self) -> Iter<T> {
         Iter { rest: self }
     }
+
+    /// Remove all the flags, returning an iterator over the flags
+    /// that were previously set.
+    ///
+    /// ```
+    /// # use enumflags2::{bitflags, make_bitflags};
+    /// # #[bitflags]
+    /// # #[derive(Clone, Copy, PartialEq, Debug)]
+    /// # #[repr(u8)]
+    /// # enum MyFlag {
+    /// #     A = 1 << 0,
+    /// #     B = 1 << 1,
+    /// #     C = 1 << 2,
+    /// # }
+    /// let mut flags = make_bitflags!(MyFlag::{A | C});
+    ///
+    /// assert_eq!(flags.drain().collect::<Vec<_>>(), [MyFlag::A, MyFlag::C]);
+    /// assert!(flags.is_empty());
+    /// ```
+    #[inline]
+    pub fn drain(&mut self) -> Iter<T> {
+        let iter = self.iter();
+        *self = BitFlags::empty();
+        iter
+    }
+
+    /// Return the flags for which the predicate returns `true`.
+    ///
+    /// ```
+    /// # use enumflags2::{bitflags, BitFlags};
+    /// # #[bitflags]
+    /// # #[derive(Clone, Copy, PartialEq, Debug)]
+    /// # #[repr(u8)]
+    /// # enum MyFlag {
+    /// #     A = 1 << 0,
+    /// #     B = 1 << 1,
+    /// #     C = 1 << 2,
+    /// # }
+    /// let flags = BitFlags::<MyFlag>::all().filter(|flag| flag != MyFlag::B);
+    /// assert_eq!(flags, MyFlag::A | MyFlag::C);
+    /// ```
+    #[inline]
+    pub fn filter<F>(self, mut predicate: F) -> Self
+    where
+        F: FnMut(T) -> bool,
+    {
+        self.iter().filter(|&flag| predicate(flag)).collect()
+    }
+
+    /// Keep only the flags for which the predicate returns `true`.
+    ///
+    /// In-place variant of [`filter`][BitFlags::filter].
+    #[inline]
+    pub fn retain<F>(&mut self, predicate: F)
+    where
+        F: FnMut(T) -> bool,
+    {
+        *self = self.filter(predicate);
+    }
+
+    /// Split the flags into the ones for which the predicate returns `true`,
+    /// and the ones for which it returns `false`.
+    ///
+    /// ```
+    /// # use enumflags2::{bitflags, BitFlags};
+    /// # #[bitflags]
+    /// # #[derive(Clone, Copy, PartialEq, Debug)]
+    /// # #[repr(u8)]
+    /// # enum MyFlag {
+    /// #     A = 1 << 0,
+    /// #     B = 1 << 1,
+    /// #     C = 1 << 2,
+    /// # }
+    /// let (b, rest) = BitFlags::<MyFlag>::all().partition(|flag| flag == MyFlag::B);
+    /// assert_eq!(b, MyFlag::B);
+    /// assert_eq!(rest, MyFlag::A | MyFlag::C);
+    /// ```
+    #[inline]
+    pub fn partition<F>(self, predicate: F) -> (Self, Self)
+    where
+        F: FnMut(T) -> bool,
+    {
+        let matching = self.filter(predicate);
+        (matching, self ^ matching)
+    }
+
+    /// Convert each flag into a flag of another type, and collect the results.
+    ///
+    /// ```
+    /// # use enumflags2::{bitflags, make_bitflags};
+    /// #[bitflags]
+    /// #[derive(Clone, Copy, PartialEq, Debug)]
+    /// #[repr(u8)]
+    /// enum Permission {
+    ///     Read = 1 << 0,
+    ///     Write = 1 << 1,
+    ///     Execute = 1 << 2,
+    /// }
+    ///
+    /// #[bitflags]
+    /// #[derive(Clone, Copy, PartialEq, Debug)]
+    /// #[repr(u16)]
+    /// enum Access {
+    ///     View = 1 << 4,
+    ///     Modify = 1 << 8,
+    /// }
+    ///
+    /// let perms = make_bitflags!(Permission::{Read | Execute});
+    /// let access = perms.map_flags(|perm| match perm {
+    ///     Permission::Read | Permission::Execute => Access::View,
+    ///     Permission::Write => Access::Modify,
+    /// });
+    /// assert_eq!(access, Access::View);
+    /// ```
+    #[inline]
+    pub fn map_flags<U, F>(self, f: F) -> BitFlags<U>
+    where
+        U: BitFlag,
+        F: FnMut(T) -> U,
+    {
+        self.iter().map(f).collect()
+    }
 }
 
 impl<T: BitFlag> IntoIterator for BitFlags<T> {
@@ -37,6 +159,15 @@ impl<T: BitFlag> IntoIterator for BitFlags<T> {
     }
 }
 
+impl<T: BitFlag> IntoIterator for &BitFlags<T> {
+    type IntoIter = Iter<T>;
+    type Item = T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
 /// Iterator that yields each flag set in a `BitFlags`.
 #[derive(Clone, Debug)]
 pub struct Iter<T: BitFlag> {
@@ -84,6 +215,7 @@ where
 
 impl<T: BitFlag> FusedIterator for Iter<T> {}
 
+// Also covers iterators over `&T`, through `From<&T> for BitFlags<T>`.
 impl<T, B> FromIterator<B> for BitFlags<T>
 where
     T: BitFlag,
@@ -112,3 +244,18 @@ where
         *self = it.into_iter().fold(*self, |acc, flag| acc | flag)
     }
 }
+
+/// Summing flags takes their union.
+impl<T, B> Sum<B> for BitFlags<T>
+where
+    T: BitFlag,
+    B: Into<BitFlags<T>>,
+{
+    #[inline]
+    fn sum<I>(it: I) -> BitFlags<T>
+    where
+        I: Iterator<Item = B>,
+    {
+        it.collect()
+    }
+}
@@ -0,0 +1,159 @@
+use crate::{BitFlag, BitFlags, FlagsPatch, Iter};
+
+/// A base set of flags, overridden by a stack of layers.
+///
+/// Each layer is a [`FlagsPatch`], which can force any flag on, force it off,
+/// or leave it alone. Later layers take precedence over earlier ones.
+/// Besides computing the final set of flags, `FlagLayers` can tell which
+/// layer decided the state of each flag.
+///
+/// ```
+/// # use enumflags2::{bitflags, BitFlags, FlagLayers, FlagsPatch, Provenance};
+/// #[bitflags(default = Color)]
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// #[repr(u8)]
+/// enum Opt {
+///     Color = 1 << 0,
+///     Verbose = 1 << 1,
+///     Pager = 1 << 2,
+/// }
+///
+/// let system = FlagsPatch::new(Opt::Pager, BitFlags::empty());
+/// let user = FlagsPatch::new(Opt::Verbose, Opt::Pager);
+/// let cli = FlagsPatch::new(BitFlags::empty(), Opt::Color);
+/// let layers = [system, user, cli];
+///
+/// let resolved = FlagLayers::from_default(&layers);
+/// assert_eq!(resolved.resolve(), Opt::Verbose);
+/// assert_eq!(resolved.decided_by(Opt::Color), Provenance::Layer(2));
+/// assert_eq!(resolved.decided_by(Opt::Pager), Provenance::Layer(1));
+///
+/// let no_layers = FlagLayers::from_default(&[]);
+/// assert_eq!(no_layers.decided_by(Opt::Color), Provenance::Base);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct FlagLayers<'a, T: BitFlag> {
+    base: BitFlags<T>,
+    layers: &'a [FlagsPatch<T>],
+}
+
+/// The source of a flag's state, as reported by [`FlagLayers`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Provenance {
+    /// No layer changed the flag, so it has the value from the base set.
+    Base,
+    /// The layer with the given index in the stack forced the flag
+    /// on or off.
+    Layer(usize),
+}
+
+impl<'a, T> FlagLayers<'a, T>
+where
+    T: BitFlag,
+{
+    /// Stack the given layers on top of `base`.
+    #[inline]
+    pub fn new<B: Into<BitFlags<T>>>(base: B, layers: &'a [FlagsPatch<T>]) -> Self {
+        FlagLayers {
+            base: base.into(),
+            layers,
+        }
+    }
+
+    /// Stack the given layers on top of [`BitFlags::default()`], which
+    /// can be [customized](index.html#customizing-default) with
+    /// `#[bitflags(default = ...)]`.
+    #[inline]
+    pub fn from_default(layers: &'a [FlagsPatch<T>]) -> Self {
+        Self::new(BitFlags::default(), layers)
+    }
+
+    /// Return the base set of flags.
+    #[inline]
+    pub fn base(self) -> BitFlags<T> {
+        self.base
+    }
+
+    /// Return the layers, in order of increasing precedence.
+    #[inline]
+    pub fn layers(self) -> &'a [FlagsPatch<T>] {
+        self.layers
+    }
+
+    /// Compute the final set of flags.
+    #[inline]
+    pub fn resolve(self) -> BitFlags<T> {
+        self.layers
+            .iter()
+            .fold(self.base, |flags, layer| layer.apply(flags))
+    }
+
+    /// Return the layer that decided the state of `flag`.
+    pub fn decided_by(self, flag: T) -> Provenance {
+        self.layers
+            .iter()
+            .rposition(|layer| layer.state(flag).is_some())
+            .map_or(Provenance::Base, Provenance::Layer)
+    }
+
+    /// Iterate over all the flags of `T`, yielding the final state of
+    /// each, along with the layer that decided it.
+    ///
+    /// ```
+    /// # use enumflags2::{bitflags, BitFlags, FlagLayers, FlagsPatch, Provenance};
+    /// # #[bitflags]
+    /// # #[derive(Clone, Copy, Debug, PartialEq)]
+    /// # #[repr(u8)]
+    /// # enum Opt {
+    /// #     Color = 1 << 0,
+    /// #     Verbose = 1 << 1,
+    /// # }
+    /// let layers = [FlagsPatch::new(Opt::Verbose, BitFlags::empty())];
+    /// let resolved = FlagLayers::new(Opt::Color, &layers);
+    ///
+    /// for (flag, enabled, provenance) in resolved.decisions() {
+    ///     match provenance {
+    ///         Provenance::Base => println!("{:?}={} (default)", flag, enabled),
+    ///         Provenance::Layer(i) => println!("{:?}={} (set by layer {})", flag, enabled, i),
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    pub fn decisions(self) -> Decisions<'a, T> {
+        Decisions {
+            layers: self,
+            resolved: self.resolve(),
+            flags: BitFlags::all().iter(),
+        }
+    }
+}
+
+/// Iterator over the state of each flag, created by [`FlagLayers::decisions`].
+#[derive(Clone, Debug)]
+pub struct Decisions<'a, T: BitFlag> {
+    layers: FlagLayers<'a, T>,
+    resolved: BitFlags<T>,
+    flags: Iter<T>,
+}
+
+impl<T> Iterator for Decisions<'_, T>
+where
+    T: BitFlag,
+{
+    type Item = (T, bool, Provenance);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let flag = self.flags.next()?;
+        Some((
+            flag,
+            self.resolved.contains(flag),
+            self.layers.decided_by(flag),
+        ))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.flags.size_hint()
+    }
+}
+
+impl<T: BitFlag> ExactSizeIterator for Decisions<'_, T> {}
@@ -0,0 +1,625 @@
+use crate::BitFlag;
+#[cfg(feature = "std")]
+use core::fmt;
+
+/// The names and values of the flags of some type, as found in
+/// [`BitFlag::LAYOUT`].
+///
+/// If `BitFlags` values are persisted, changing the value of a flag makes
+/// the old data mean something else. To catch this, save the layout
+/// (with the `serde` feature, it implements `Serialize`), and compare it
+/// against the current one in a test with [`SavedLayout`].
+///
+/// The [`fingerprint`][FlagLayout::fingerprint] of a layout is a hash of the
+/// names and values of the flags, which doesn't depend on their order.
+/// The algorithm is fixed, so fingerprints can be persisted too.
+///
+/// ```
+/// # use enumflags2::{bitflags, FlagLayout};
+/// #[bitflags]
+/// #[repr(u8)]
+/// #[derive(Clone, Copy)]
+/// enum Perm {
+///     Read = 1 << 0,
+///     Write = 1 << 1,
+/// }
+///
+/// #[bitflags]
+/// #[repr(u8)]
+/// #[derive(Clone, Copy)]
+/// enum Reordered {
+///     Write = 1 << 1,
+///     Read = 1 << 0,
+/// }
+///
+/// let layout = FlagLayout::of::<Perm>();
+/// assert_eq!(layout.get("Write"), Some(0b10));
+/// assert_eq!(layout.fingerprint(), FlagLayout::of::<Reordered>().fingerprint());
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FlagLayout<'a> {
+    flags: &'a [(&'a str, u128)],
+}
+
+impl<'a> FlagLayout<'a> {
+    /// Create a layout from the names and values of the flags.
+    #[inline(always)]
+    pub const fn new(flags: &'a [(&'a str, u128)]) -> Self {
+        FlagLayout { flags }
+    }
+
+    /// Return the names and values of the flags.
+    #[inline(always)]
+    pub const fn flags(&self) -> &'a [(&'a str, u128)] {
+        self.flags
+    }
+
+    /// Return the value of the flag with the given name.
+    pub fn get(&self, name: &str) -> Option<u128> {
+        self.flags
+            .iter()
+            .find(|&&(flag, _)| flag == name)
+            .map(|&(_, bits)| bits)
+    }
+
+    /// Compute the fingerprint of the layout.
+    pub const fn fingerprint(&self) -> u64 {
+        let mut fingerprint = 0u64;
+        let mut i = 0;
+        while i < self.flags.len() {
+            let (name, bits) = self.flags[i];
+            fingerprint = fingerprint.wrapping_add(flag_hash(name, bits));
+            i += 1;
+        }
+        fingerprint
+    }
+}
+
+impl FlagLayout<'static> {
+    /// Return the layout of `T`.
+    #[inline(always)]
+    pub fn of<T: BitFlag>() -> Self {
+        FlagLayout::new(T::LAYOUT)
+    }
+}
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// The FNV-1a hash of the name, a zero byte, and the little-endian bytes of
+/// the value, passed through the finalizer of SplitMix64. The sum of these
+/// hashes is the fingerprint of a layout.
+const fn flag_hash(name: &str, bits: u128) -> u64 {
+    let mut hash = FNV_OFFSET;
+    let name = name.as_bytes();
+    let mut i = 0;
+    while i < name.len() {
+        hash = (hash ^ name[i] as u64).wrapping_mul(FNV_PRIME);
+        i += 1;
+    }
+
+    hash = hash.wrapping_mul(FNV_PRIME);
+    let bits = bits.to_le_bytes();
+    let mut i = 0;
+    while i < bits.len() {
+        hash = (hash ^ bits[i] as u64).wrapping_mul(FNV_PRIME);
+        i += 1;
+    }
+
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^ (hash >> 31)
+}
+
+#[cfg(feature = "std")]
+impl FlagLayout<'_> {
+    /// Compare the layout against one saved earlier.
+    pub fn changes_since(&self, saved: &SavedLayout) -> LayoutDiff {
+        let mut changes = vec![];
+        for (name, old) in &saved.flags {
+            match self.get(name) {
+                Some(new) if new == *old => {}
+                Some(new) => changes.push(LayoutChange::Moved {
+                    name: name.clone(),
+                    old: *old,
+                    new,
+                }),
+                None => changes.push(LayoutChange::Removed {
+                    name: name.clone(),
+                    bits: *old,
+                }),
+            }
+        }
+
+        for &(name, bits) in self.flags {
+            if !saved.flags.iter().any(|(saved_name, _)| saved_name == name) {
+                changes.push(LayoutChange::Added {
+                    name: name.to_owned(),
+                    bits,
+                });
+            }
+        }
+
+        LayoutDiff { changes }
+    }
+}
+
+/// A [`FlagLayout`] that was saved earlier.
+///
+/// With the `serde` feature, a `SavedLayout` can be deserialized from a
+/// serialized `FlagLayout`, in any format. The serialized form has the
+/// `flags`, as a list of `name` and `bits` pairs, and the `fingerprint`.
+/// Both the bits and the fingerprint are hexadecimal strings, since many
+/// formats don't support `u128`. The fingerprint is optional when deserializing, but
+/// if present, it must match the flags.
+///
+/// Checking the saved layout from a test catches changes that would make
+/// the persisted values of the flags mean something else:
+///
+/// ```
+/// # use enumflags2::{bitflags, FlagLayout, SavedLayout};
+/// #[bitflags]
+/// #[repr(u8)]
+/// #[derive(Clone, Copy)]
+/// enum Perm {
+///     Read = 1 << 0,
+///     Exec = 1 << 1,
+///     Admin = 1 << 3,
+/// }
+///
+/// // Usually deserialized from a file, created earlier by serializing
+/// // `FlagLayout::of::<Perm>()`.
+/// let saved = SavedLayout::from(FlagLayout::new(&[("Read", 1), ("Write", 2), ("Admin", 4)]));
+///
+/// let diff = FlagLayout::of::<Perm>().changes_since(&saved);
+/// assert!(!diff.is_compatible());
+/// assert_eq!(
+///     diff.to_string(),
+///     "`Write` (0x2) was removed\n\
+///      `Admin` moved from 0x4 to 0x8\n\
+///      `Exec` (0x2) was added\n",
+/// );
+/// ```
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SavedLayout {
+    flags: Vec<(String, u128)>,
+}
+
+#[cfg(feature = "std")]
+impl SavedLayout {
+    /// Return the names and values of the flags.
+    pub fn flags(&self) -> &[(String, u128)] {
+        &self.flags
+    }
+
+    /// Compute the fingerprint of the layout, in the same way as
+    /// [`FlagLayout::fingerprint`].
+    pub fn fingerprint(&self) -> u64 {
+        self.flags.iter().fold(0u64, |sum, (name, bits)| {
+            sum.wrapping_add(flag_hash(name, *bits))
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<FlagLayout<'_>> for SavedLayout {
+    fn from(layout: FlagLayout<'_>) -> Self {
+        SavedLayout {
+            flags: layout
+                .flags
+                .iter()
+                .map(|&(name, bits)| (name.to_owned(), bits))
+                .collect(),
+        }
+    }
+}
+
+/// The differences between a [`SavedLayout`] and the current one, as
+/// returned by [`FlagLayout::changes_since`].
+///
+/// The `Display` implementation lists the changes, one per line.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LayoutDiff {
+    changes: Vec<LayoutChange>,
+}
+
+/// A change to a single flag, reported by [`LayoutDiff`].
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LayoutChange {
+    /// The flag now has a different value.
+    Moved {
+        /// The name of the flag.
+        name: String,
+        /// The value in the saved layout.
+        old: u128,
+        /// The value in the current layout.
+        new: u128,
+    },
+    /// The flag is only in the saved layout.
+    Removed {
+        /// The name of the flag.
+        name: String,
+        /// The value in the saved layout.
+        bits: u128,
+    },
+    /// The flag is only in the current layout.
+    Added {
+        /// The name of the flag.
+        name: String,
+        /// The value in the current layout.
+        bits: u128,
+    },
+}
+
+#[cfg(feature = "std")]
+impl LayoutDiff {
+    /// Return the changes: first the moved and removed flags, in the order
+    /// of the saved layout, then the added flags.
+    pub fn changes(&self) -> &[LayoutChange] {
+        &self.changes
+    }
+
+    /// Returns true if the layouts are the same.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Returns true if values saved with the old layout mean the same
+    /// with the current one, which is the case if flags were only added.
+    pub fn is_compatible(&self) -> bool {
+        self.changes
+            .iter()
+            .all(|change| matches!(change, LayoutChange::Added { .. }))
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for LayoutDiff {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(fmt, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for LayoutChange {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutChange::Moved { name, old, new } => {
+                write!(fmt, "`{}` moved from {:#x} to {:#x}", name, old, new)
+            }
+            LayoutChange::Removed { name, bits } => {
+                write!(fmt, "`{}` ({:#x}) was removed", name, bits)
+            }
+            LayoutChange::Added { name, bits } => write!(fmt, "`{}` ({:#x}) was added", name, bits),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+mod impl_serde {
+    use super::FlagLayout;
+    use serde::ser::{Serialize, SerializeSeq, SerializeStruct, Serializer};
+
+    impl Serialize for FlagLayout<'_> {
+        fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+            serialize_layout(self.flags.iter().copied(), self.fingerprint(), s)
+        }
+    }
+
+    /// Serialize the flags and the fingerprint of a layout.
+    pub(super) fn serialize_layout<'a, I, S>(
+        flags: I,
+        fingerprint: u64,
+        s: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        I: ExactSizeIterator<Item = (&'a str, u128)> + Clone,
+        S: Serializer,
+    {
+        let mut layout = s.serialize_struct("FlagLayout", 2)?;
+        layout.serialize_field("fingerprint", &Fingerprint(fingerprint))?;
+        layout.serialize_field("flags", &Flags(flags))?;
+        layout.end()
+    }
+
+    struct Fingerprint(u64);
+
+    impl Serialize for Fingerprint {
+        fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+            s.collect_str(&format_args!("{:016x}", self.0))
+        }
+    }
+
+    struct Flags<I>(I);
+
+    impl<'a, I> Serialize for Flags<I>
+    where
+        I: ExactSizeIterator<Item = (&'a str, u128)> + Clone,
+    {
+        fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+            let mut seq = s.serialize_seq(Some(self.0.len()))?;
+            for (name, bits) in self.0.clone() {
+                seq.serialize_element(&Flag { name, bits })?;
+            }
+            seq.end()
+        }
+    }
+
+    struct Flag<'a> {
+        name: &'a str,
+        bits: u128,
+    }
+
+    impl Serialize for Flag<'_> {
+        fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+            let mut flag = s.serialize_struct("Flag", 2)?;
+            flag.serialize_field("name", self.name)?;
+            flag.serialize_field("bits", &Bits(self.bits))?;
+            flag.end()
+        }
+    }
+
+    /// The bits of a flag, as a hexadecimal string, since many formats
+    /// don't support `u128`.
+    struct Bits(u128);
+
+    impl Serialize for Bits {
+        fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+            s.collect_str(&format_args!("{:#x}", self.0))
+        }
+    }
+}
+
+// Implemented by hand, since serde may be built without `std`, which
+// `Vec` and `String` need.
+#[cfg(all(feature = "serde", feature = "std"))]
+mod impl_serde_saved {
+    use super::impl_serde::serialize_layout;
+    use super::SavedLayout;
+    use core::fmt;
+    use serde::de::{
+        self, Deserialize, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor,
+    };
+    use serde::ser::{Serialize, Serializer};
+
+    const LAYOUT_FIELDS: &[&str] = &["fingerprint", "flags"];
+    const FLAG_FIELDS: &[&str] = &["name", "bits"];
+
+    impl Serialize for SavedLayout {
+        fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+            let flags = self.flags.iter().map(|(name, bits)| (name.as_str(), *bits));
+            serialize_layout(flags, self.fingerprint(), s)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for SavedLayout {
+        fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+            d.deserialize_struct("FlagLayout", LAYOUT_FIELDS, LayoutVisitor)
+        }
+    }
+
+    /// Deserializes a field name, which must be one of the given fields.
+    struct Field(&'static [&'static str]);
+
+    impl<'de> DeserializeSeed<'de> for Field {
+        type Value = &'static str;
+
+        fn deserialize<D: Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+            d.deserialize_identifier(self)
+        }
+    }
+
+    impl<'de> Visitor<'de> for Field {
+        type Value = &'static str;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt.write_str("a field name")
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+            self.0
+                .iter()
+                .find(|&&field| field == value)
+                .copied()
+                .ok_or_else(|| E::unknown_field(value, self.0))
+        }
+    }
+
+    struct LayoutVisitor;
+
+    impl<'de> Visitor<'de> for LayoutVisitor {
+        type Value = SavedLayout;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt.write_str("struct FlagLayout")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let fingerprint = seq
+                .next_element_seed(FingerprintVisitor)?
+                .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+            let flags = seq
+                .next_element_seed(FlagsVisitor)?
+                .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+            check_fingerprint(SavedLayout { flags }, Some(fingerprint))
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut fingerprint = None;
+            let mut flags = None;
+            while let Some(key) = map.next_key_seed(Field(LAYOUT_FIELDS))? {
+                match key {
+                    "fingerprint" if fingerprint.is_none() => {
+                        fingerprint = Some(map.next_value_seed(FingerprintVisitor)?)
+                    }
+                    "flags" if flags.is_none() => flags = Some(map.next_value_seed(FlagsVisitor)?),
+                    _ => return Err(de::Error::duplicate_field(key)),
+                }
+            }
+
+            let saved = SavedLayout {
+                flags: flags.ok_or_else(|| de::Error::missing_field("flags"))?,
+            };
+            check_fingerprint(saved, fingerprint)
+        }
+    }
+
+    fn check_fingerprint<E: de::Error>(
+        saved: SavedLayout,
+        fingerprint: Option<u64>,
+    ) -> Result<SavedLayout, E> {
+        match fingerprint {
+            Some(fingerprint) if fingerprint != saved.fingerprint() => {
+                Err(E::custom("fingerprint doesn't match the flags"))
+            }
+            _ => Ok(saved),
+        }
+    }
+
+    struct FingerprintVisitor;
+
+    impl<'de> DeserializeSeed<'de> for FingerprintVisitor {
+        type Value = u64;
+
+        fn deserialize<D: Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+            d.deserialize_str(self)
+        }
+    }
+
+    impl<'de> Visitor<'de> for FingerprintVisitor {
+        type Value = u64;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt.write_str("a hexadecimal fingerprint")
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+            u64::from_str_radix(value, 16)
+                .map_err(|_| E::invalid_value(de::Unexpected::Str(value), &self))
+        }
+    }
+
+    struct FlagsVisitor;
+
+    impl<'de> DeserializeSeed<'de> for FlagsVisitor {
+        type Value = Vec<(String, u128)>;
+
+        fn deserialize<D: Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+            d.deserialize_seq(self)
+        }
+    }
+
+    impl<'de> Visitor<'de> for FlagsVisitor {
+        type Value = Vec<(String, u128)>;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt.write_str("a list of flags")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut flags = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(flag) = seq.next_element_seed(FlagVisitor)? {
+                flags.push(flag);
+            }
+            Ok(flags)
+        }
+    }
+
+    struct FlagVisitor;
+
+    impl<'de> DeserializeSeed<'de> for FlagVisitor {
+        type Value = (String, u128);
+
+        fn deserialize<D: Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+            d.deserialize_struct("Flag", FLAG_FIELDS, self)
+        }
+    }
+
+    impl<'de> Visitor<'de> for FlagVisitor {
+        type Value = (String, u128);
+
+        fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt.write_str("struct Flag")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let name = seq
+                .next_element_seed(NameVisitor)?
+                .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+            let bits = seq
+                .next_element_seed(BitsVisitor)?
+                .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+            Ok((name, bits))
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut name = None;
+            let mut bits = None;
+            while let Some(key) = map.next_key_seed(Field(FLAG_FIELDS))? {
+                match key {
+                    "name" if name.is_none() => name = Some(map.next_value_seed(NameVisitor)?),
+                    "bits" if bits.is_none() => bits = Some(map.next_value_seed(BitsVisitor)?),
+                    _ => return Err(de::Error::duplicate_field(key)),
+                }
+            }
+
+            Ok((
+                name.ok_or_else(|| de::Error::missing_field("name"))?,
+                bits.ok_or_else(|| de::Error::missing_field("bits"))?,
+            ))
+        }
+    }
+
+    struct NameVisitor;
+
+    impl<'de> DeserializeSeed<'de> for NameVisitor {
+        type Value = String;
+
+        fn deserialize<D: Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+            d.deserialize_str(self)
+        }
+    }
+
+    impl<'de> Visitor<'de> for NameVisitor {
+        type Value = String;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt.write_str("a flag name")
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+            Ok(value.to_owned())
+        }
+    }
+
+    struct BitsVisitor;
+
+    impl<'de> DeserializeSeed<'de> for BitsVisitor {
+        type Value = u128;
+
+        fn deserialize<D: Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+            d.deserialize_str(self)
+        }
+    }
+
+    impl<'de> Visitor<'de> for BitsVisitor {
+        type Value = u128;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt.write_str("hexadecimal bits, starting with `0x`")
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+            value
+                .strip_prefix("0x")
+                .and_then(|hex| u128::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| E::invalid_value(de::Unexpected::Str(value), &self))
+        }
+    }
+}
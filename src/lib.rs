@@ -49,8 +49,14 @@
 //! ## Optional Feature Flags
 //!
 //! - [`serde`](https://serde.rs/) implements `Serialize` and `Deserialize`
-//!   for `BitFlags<T>`.
-//! - `std` implements `std::error::Error` for `FromBitsError`.
+//!   for `BitFlags<T>`, and for `FlagLayout` and `SavedLayout`.
+//! - `std` implements `std::error::Error` for `FromBitsError` and the other
+//!   error types on compilers older than 1.81, which lack `core::error::Error`.
+//!   It also enables `Predicate`, which parses flag expressions at runtime,
+//!   and `SavedLayout`, which checks persisted flag values for compatibility.
+//! - [`rayon`](https://docs.rs/rayon) implements `FromParallelIterator` and
+//!   `ParallelExtend` for `BitFlags<T>`, and provides union and intersection
+//!   reductions over parallel iterators.
 //!
 //! ## `const fn`-compatible APIs
 //!
@@ -95,6 +101,239 @@
 //! assert_eq!(BitFlags::default(), Test::B | Test::C);
 //! ```
 //!
+//! ## Reserved bits
+//!
+//! Bits that aren't flags, but have a meaning in your format (for example,
+//! because they are reserved for future use), can be given a name. It will
+//! show up in the message of a [`FromBitsError`]. Reserved values must fit
+//! in the `repr`, and may not overlap the flags or each other:
+//!
+//! ```
+//! # use enumflags2::{BitFlags, bitflags};
+//! #[bitflags(reserved(Compressed = 1 << 6, Extension = 1 << 7))]
+//! #[repr(u8)]
+//! #[derive(Copy, Clone, Debug, PartialEq)]
+//! enum Header {
+//!     A = 0b0001,
+//!     B = 0b0010,
+//! }
+//!
+//! let error = BitFlags::<Header>::from_bits(0b1001_0001).unwrap_err();
+//! assert_eq!(
+//!     error.to_string(),
+//!     "Invalid bits for BitFlags<Header>(0b1, A): bit 4, bit 7 (Extension)",
+//! );
+//! ```
+//!
+//! ## Versioned values
+//!
+//! When flags that are persisted get new values, values written by older
+//! versions of a program can still be read correctly. Mark the flags
+//! whose value changed with `#[bitflag(previously = ..., until = ...)]`,
+//! and read old values with [`BitFlags::from_bits_versioned`]. Versions are
+//! plain `u32`s, and `until` is the first version that uses the current value.
+//! As with the current values, no two flags may share a bit in any of the
+//! older layouts, which is checked at compile time.
+//!
+//! ```
+//! # use enumflags2::{BitFlags, bitflags};
+//! #[bitflags]
+//! #[repr(u8)]
+//! #[derive(Copy, Clone, Debug, PartialEq)]
+//! enum Perm {
+//!     Read = 1 << 0,
+//!     #[bitflag(previously = 1 << 4, until = 2)]
+//!     Write = 1 << 1,
+//! }
+//!
+//! let old = BitFlags::<Perm>::from_bits_versioned(0b1_0001, 1).unwrap();
+//! assert_eq!(old, Perm::Read | Perm::Write);
+//! assert_eq!(BitFlags::<Perm>::from_bits_versioned(0b11, 2).unwrap(), old);
+//!
+//! // Reading an old value as a current one fails, but the error points out
+//! // that the value would be valid in an older version.
+//! let error = BitFlags::<Perm>::from_bits(0b1_0001).unwrap_err();
+//! assert_eq!(error.legacy_layout(), Some(2));
+//! assert_eq!(
+//!     error.to_string(),
+//!     "Invalid bits for BitFlags<Perm>(0b1, Read): bit 4 (Write before version 2); \
+//!      valid in the layout used before version 2",
+//! );
+//! ```
+//!
+//! With the `serde` feature, `FromVersion` and `deserialize_from_version`
+//! deserialize values written by an older version.
+//!
+//! ## Renaming flags
+//!
+//! By default, flags are displayed and parsed by their variant name.
+//! `#[bitflags(rename_all = "...")]` changes the naming convention for all
+//! flags, using the same rules as serde, such as `"snake_case"` or
+//! `"kebab-case"`. `#[bitflag(rename = "...")]` sets the name of a single
+//! flag, and `#[bitflag(alias = "...")]` adds a name that is accepted
+//! when parsing, which keeps old names working after a rename.
+//!
+//! ```
+//! # use enumflags2::{BitFlags, FlagsPatch, bitflags};
+//! #[bitflags(rename_all = "snake_case")]
+//! #[repr(u8)]
+//! #[derive(Copy, Clone, Debug, PartialEq)]
+//! enum Perm {
+//!     ReadOnly = 1 << 0,
+//!     #[bitflag(rename = "rw", alias = "read_write")]
+//!     ReadWrite = 1 << 1,
+//! }
+//!
+//! assert_eq!((Perm::ReadOnly | Perm::ReadWrite).to_string(), "read_only | rw");
+//! let patch: FlagsPatch<Perm> = "+read_write".parse().unwrap();
+//! assert_eq!(patch.to_string(), "+rw");
+//! ```
+//!
+//! The names are used by `Display`, by [`FlagsPatch`] and `Predicate`
+//! when parsing, and, with the `serde` feature, by `serialize_names` and
+//! `deserialize_names`. `Debug` always uses the variant names.
+//!
+//! The `Debug` output of `BitFlags<T>` takes the variant names from the
+//! `#[bitflags]` attribute, rather than from the `Debug` implementation
+//! of `T`. If `T` has a hand-written `Debug` implementation, it is no longer
+//! used when formatting `BitFlags<T>`.
+//!
+//! `#[bitflags(display, from_str)]` implements `Display` and `FromStr` for
+//! the enum itself, using the same names. Neither `BitFlags<T>` nor these
+//! implementations require `T: Debug`.
+//!
+//! ```
+//! # use enumflags2::{bitflags, ParseFlagError};
+//! #[bitflags(rename_all = "lowercase", display, from_str)]
+//! #[repr(u8)]
+//! #[derive(Copy, Clone, PartialEq)]
+//! enum Perm {
+//!     Read = 1 << 0,
+//!     #[bitflag(alias = "modify")]
+//!     Write = 1 << 1,
+//! }
+//!
+//! assert_eq!(Perm::Write.to_string(), "write");
+//! assert!("modify".parse::<Perm>() == Ok(Perm::Write));
+//! assert!("Read".parse::<Perm>() == Err(ParseFlagError));
+//! ```
+//!
+//! Similarly, `#[bitflags(try_from)]` implements `TryFrom<BitFlags<T>>` for
+//! the enum, which succeeds when exactly one flag is set (see
+//! [`SingleFlagError`]).
+//!
+//! ## Accessors
+//!
+//! `#[bitflags(accessors)]` generates a trait, named after the enum with
+//! an `Accessors` suffix, with methods for each flag on `BitFlags<T>`.
+//! Use `accessors = Name` to choose another name for the trait. The methods
+//! are named after the flag in `snake_case`, and document the flag with
+//! its doc comment.
+//!
+//! ```
+//! # use enumflags2::{BitFlags, bitflags};
+//! #[bitflags(accessors)]
+//! #[repr(u8)]
+//! #[derive(Copy, Clone, Debug, PartialEq)]
+//! enum Feature {
+//!     /// Skip validation for trusted input.
+//!     FastPath = 1 << 0,
+//!     Compression = 1 << 1,
+//! }
+//!
+//! let mut features = BitFlags::<Feature>::empty().with_fast_path();
+//! features.set_compression(true);
+//! assert!(features.is_fast_path() && features.is_compression());
+//! ```
+//!
+//! ## Structs of bools
+//!
+//! `#[derive(IntoBitFlags)]` converts between a struct with one `bool` field
+//! per flag and `BitFlags<T>`, in both directions. The flag type is named with
+//! `#[bitflag(flags = ...)]`. Fields are matched with the flag of the same
+//! name in `PascalCase`, or the one given with `#[bitflag(flag = ...)]`.
+//! Every flag has to correspond to exactly one field, so that no
+//! information is lost, which is checked at compile time.
+//!
+//! ```
+//! # use enumflags2::{BitFlags, IntoBitFlags, bitflags};
+//! #[bitflags]
+//! #[repr(u8)]
+//! #[derive(Copy, Clone, Debug, PartialEq)]
+//! enum Perm {
+//!     Read = 1 << 0,
+//!     Write = 1 << 1,
+//! }
+//!
+//! #[derive(IntoBitFlags)]
+//! #[bitflag(flags = Perm)]
+//! struct Options {
+//!     read: bool,
+//!     #[bitflag(flag = Write)]
+//!     writable: bool,
+//! }
+//!
+//! let flags = BitFlags::from(Options { read: true, writable: false });
+//! assert_eq!(flags, Perm::Read);
+//! assert!(Options::from(BitFlags::all()).writable);
+//! ```
+//!
+//! `#[bitflags(bools)]` generates such a struct for an enum, named after the
+//! enum with a `Bools` suffix, or as given with `bools = Name`. Its fields
+//! are the names of the flags in `snake_case`. With the `serde` feature,
+//! the struct is serialized as a map from the names of its fields to
+//! `bool`s, or from the names of the flags, for flags renamed with `rename`
+//! or `rename_all`. The names and aliases of the flags are accepted when
+//! deserializing, and flags that are missing are not set.
+//!
+//! ```
+//! # use enumflags2::{BitFlags, bitflags};
+//! #[bitflags(bools)]
+//! #[repr(u8)]
+//! #[derive(Copy, Clone, Debug, PartialEq)]
+//! enum Perm {
+//!     Read = 1 << 0,
+//!     ReadWrite = 1 << 1,
+//! }
+//!
+//! let bools = PermBools { read: true, ..Default::default() };
+//! assert_eq!(BitFlags::from(bools), Perm::Read);
+//! ```
+//!
+//! ## Subsets
+//!
+//! An enum whose flags are all flags of a larger enum, with the same names
+//! and bits, can declare so with `#[bitflags(subset_of = ...)]`. This is
+//! checked at compile time, and implements [`SubsetOf`], which provides
+//! conversions between the two:
+//!
+//! ```
+//! # use enumflags2::{BitFlags, bitflags};
+//! #[bitflags]
+//! #[repr(u8)]
+//! #[derive(Copy, Clone, Debug, PartialEq)]
+//! enum FsFlags {
+//!     Read = 1 << 0,
+//!     Write = 1 << 1,
+//!     Sync = 1 << 2,
+//! }
+//!
+//! #[bitflags(subset_of = FsFlags)]
+//! #[repr(u8)]
+//! #[derive(Copy, Clone, Debug, PartialEq)]
+//! enum OpenFlags {
+//!     Read = 1 << 0,
+//!     Write = 1 << 1,
+//! }
+//!
+//! let open = OpenFlags::Read | OpenFlags::Write;
+//! assert_eq!(open.widen::<FsFlags>(), FsFlags::Read | FsFlags::Write);
+//!
+//! let fs = FsFlags::Read | FsFlags::Sync;
+//! assert_eq!(fs.project::<OpenFlags>(), OpenFlags::Read);
+//! assert_eq!(fs.try_project::<OpenFlags>().unwrap_err().unexpected(), FsFlags::Sync);
+//! ```
+//!
 //! [const-trait-rfc]: https://github.com/rust-lang/rfcs/pull/2632
 #![warn(missing_docs)]
 #![cfg_attr(all(not(test), not(feature = "std")), no_std)]
@@ -109,6 +348,7 @@ extern crate enumflags2_derive;
 
 #[doc(hidden)]
 pub use enumflags2_derive::bitflags_internal as bitflags;
+pub use enumflags2_derive::IntoBitFlags;
 
 // Internal macro: expand into a separate copy for each supported numeric type.
 macro_rules! for_each_uint {
@@ -130,6 +370,24 @@ macro_rules! for_each_uint {
 /// A trait automatically implemented by `#[bitflags]` to make the enum
 /// a valid type parameter for `BitFlags<T>`.
 pub trait BitFlag: Copy + Clone + 'static + _internal::RawBitFlags {
+    /// The name and value of each flag, in declaration order. See [`FlagLayout`]
+    /// for checking that the values stored by an earlier version of a program
+    /// are still interpreted the same way.
+    ///
+    /// ```
+    /// # use enumflags2::{bitflags, BitFlag};
+    /// #[bitflags]
+    /// #[repr(u8)]
+    /// #[derive(Clone, Copy)]
+    /// enum MyFlag {
+    ///     One = 1 << 0,
+    ///     Two = 1 << 1,
+    /// }
+    ///
+    /// assert_eq!(MyFlag::LAYOUT, [("One", 1), ("Two", 2)]);
+    /// ```
+    const LAYOUT: &'static [(&'static str, u128)] = &[];
+
     /// Create a `BitFlags` with no flags set (in other words, with a value of 0).
     ///
     /// This is a convenience reexport of [`BitFlags::empty`]. It can be called with
@@ -291,6 +549,43 @@ pub trait BitFlag: Copy + Clone + 'static + _internal::RawBitFlags {
     unsafe fn from_bits_unchecked(bits: Self::Numeric) -> BitFlags<Self> {
         BitFlags::from_bits_unchecked(bits)
     }
+
+    /// A human-readable label for the flag, set with
+    /// `#[bitflag(label = "...")]`. Defaults to the name of the flag.
+    ///
+    /// ```
+    /// # use enumflags2::{bitflags, BitFlag};
+    /// #[bitflags]
+    /// #[repr(u8)]
+    /// #[derive(Clone, Copy)]
+    /// enum MyFlag {
+    ///     /// Keep a copy of every change.
+    ///     #[bitflag(label = "Keep history")]
+    ///     History = 1 << 0,
+    ///     Sync = 1 << 1,
+    /// }
+    ///
+    /// assert_eq!(MyFlag::History.label(), "Keep history");
+    /// assert_eq!(MyFlag::History.description(), Some("Keep a copy of every change."));
+    /// assert_eq!(MyFlag::Sync.label(), "Sync");
+    /// assert_eq!(MyFlag::Sync.description(), None);
+    /// ```
+    fn label(self) -> &'static str {
+        let bits = self.bits();
+        Self::LABELS
+            .iter()
+            .find(|&&(flag, _)| flag == bits)
+            .map_or("", |&(_, label)| label)
+    }
+
+    /// The doc comment of the flag, if it has one. See [`label`][BitFlag::label].
+    fn description(self) -> Option<&'static str> {
+        let bits = self.bits();
+        Self::DESCRIPTIONS
+            .iter()
+            .find(|&&(flag, _)| flag == bits)
+            .map(|&(_, description)| description)
+    }
 }
 
 /// While the module is public, this is only the case because it needs to be
@@ -324,11 +619,40 @@ pub mod _internal {
         /// A value with all flag bits set.
         const ALL_BITS: Self::Numeric;
 
+        /// `ALL_BITS`, widened to `u128` for use in const generics. Defaults
+        /// to all ones, which disables the compile-time checks in `ConstFlags`.
+        const ALL_BITS_U128: u128 = !0;
+
         /// The name of the type for debug formatting purposes.
         ///
         /// This is typically `BitFlags<EnumName>`
         const BITFLAGS_TYPE_NAME: &'static str;
 
+        /// Masks of bits that don't correspond to flags, but have a name
+        /// to be shown in diagnostics.
+        const RESERVED_BITS: &'static [(Self::Numeric, &'static str)] = &[];
+
+        /// The previous values of flags, as `(current, previous, until)`:
+        /// before version `until`, the flag `current` had the value `previous`.
+        const HISTORY: &'static [(Self::Numeric, Self::Numeric, u32)] = &[];
+
+        /// The canonical name of each flag, used when formatting and
+        /// serializing flags by name. If empty, the `Debug` output is used.
+        const NAMES: &'static [(Self::Numeric, &'static str)] = &[];
+
+        /// Additional names accepted when parsing flags.
+        const ALIASES: &'static [(Self::Numeric, &'static str)] = &[];
+
+        /// The character of each flag in the symbolic representation,
+        /// in declaration order.
+        const SYMBOLS: &'static [(Self::Numeric, char)] = &[];
+
+        /// The human-readable label of each flag.
+        const LABELS: &'static [(Self::Numeric, &'static str)] = &[];
+
+        /// The doc comment of each flag that has one.
+        const DESCRIPTIONS: &'static [(Self::Numeric, &'static str)] = &[];
+
         /// Return the bits as a number type.
         fn bits(self) -> Self::Numeric;
     }
@@ -351,35 +675,83 @@ pub mod _internal {
         + fmt::Binary
         + Copy
         + Clone
+        + Send
+        + Sync
     {
         const ONE: Self;
 
+        type NonZero: Copy + Eq + Hash + Send + Sync + 'static;
+
         fn is_power_of_two(self) -> bool;
         fn count_ones(self) -> u32;
+        fn trailing_zeros(self) -> u32;
         fn wrapping_neg(self) -> Self;
+        fn wrapping_sub(self, other: Self) -> Self;
+        fn to_non_zero(self) -> Option<Self::NonZero>;
+        fn from_non_zero(value: Self::NonZero) -> Self;
+        fn truncate_from_u128(value: u128) -> Self;
+        fn to_u128(self) -> u128;
     }
 
-    for_each_uint! { $ty $hide_docs =>
-        impl BitFlagNum for $ty {
-            const ONE: Self = 1;
+    macro_rules! impl_bitflag_num {
+        ( $($ty:ident => $non_zero:ident),* ) => {
+            $(
+                impl BitFlagNum for $ty {
+                    const ONE: Self = 1;
 
-            fn is_power_of_two(self) -> bool {
-                <$ty>::is_power_of_two(self)
-            }
+                    type NonZero = ::core::num::$non_zero;
 
-            fn count_ones(self) -> u32 {
-                <$ty>::count_ones(self)
-            }
+                    fn is_power_of_two(self) -> bool {
+                        <$ty>::is_power_of_two(self)
+                    }
 
-            fn wrapping_neg(self) -> Self {
-                <$ty>::wrapping_neg(self)
-            }
+                    fn count_ones(self) -> u32 {
+                        <$ty>::count_ones(self)
+                    }
+
+                    fn trailing_zeros(self) -> u32 {
+                        <$ty>::trailing_zeros(self)
+                    }
+
+                    fn wrapping_neg(self) -> Self {
+                        <$ty>::wrapping_neg(self)
+                    }
+
+                    fn wrapping_sub(self, other: Self) -> Self {
+                        <$ty>::wrapping_sub(self, other)
+                    }
+
+                    fn to_non_zero(self) -> Option<Self::NonZero> {
+                        ::core::num::$non_zero::new(self)
+                    }
+
+                    fn from_non_zero(value: Self::NonZero) -> Self {
+                        value.get()
+                    }
+
+                    fn truncate_from_u128(value: u128) -> Self {
+                        value as $ty
+                    }
+
+                    fn to_u128(self) -> u128 {
+                        self as u128
+                    }
+                }
+            )*
         }
     }
 
+    impl_bitflag_num! {
+        u8 => NonZeroU8,
+        u16 => NonZeroU16,
+        u32 => NonZeroU32,
+        u64 => NonZeroU64,
+        u128 => NonZeroU128
+    }
+
     // Re-export libcore so the macro doesn't inject "extern crate" downstream.
     pub mod core {
-        pub use core::{convert, ops, option};
+        pub use core::{convert, fmt, ops, option, result, str};
     }
 
     pub struct AssertionSucceeded;
@@ -403,26 +775,325 @@ pub mod _internal {
         type Status = AssertionFailed;
     }
 
+    pub trait HistoricalFlagsAreDisjoint {
+        type X;
+    }
+    impl HistoricalFlagsAreDisjoint for AssertionSucceeded {
+        type X = ();
+    }
+
+    pub use crate::versioned::history_is_disjoint;
+
+    pub trait ReservedBitsAreNotFlags {
+        type X;
+    }
+    impl ReservedBitsAreNotFlags for AssertionSucceeded {
+        type X = ();
+    }
+
+    pub trait ReservedBitsAreDistinct {
+        type X;
+    }
+    impl ReservedBitsAreDistinct for AssertionSucceeded {
+        type X = ();
+    }
+
+    pub const fn disjoint(a: u128, b: u128) -> bool {
+        a & b == 0
+    }
+
     pub const fn next_bit(x: u128) -> u128 {
         1 << x.trailing_ones()
     }
+
+    pub use enumflags2_derive::flag_expr_internal;
+
+    pub use enumflags2_derive::bitflags_match_internal;
+
+    /// Used by the `TryFrom<BitFlags<T>>` implementation generated for `T`.
+    #[inline]
+    pub fn single_flag<T: crate::BitFlag>(
+        flags: crate::BitFlags<T>,
+    ) -> Result<T, crate::SingleFlagError<T>> {
+        flags.exactly_one().ok_or(crate::SingleFlagError { flags })
+    }
+
+    pub use crate::formatting::{fmt_flag_name, parse_flag_name};
+
+    pub trait BoolFieldsAreDistinct {
+        type X;
+    }
+    impl BoolFieldsAreDistinct for AssertionSucceeded {
+        type X = ();
+    }
+
+    pub trait BoolFieldsCoverAllFlags {
+        type X;
+    }
+    impl BoolFieldsCoverAllFlags for AssertionSucceeded {
+        type X = ();
+    }
+
+    pub use crate::__enumflags2_impl_bools_serde as impl_bools_serde;
+    #[cfg(feature = "serde")]
+    pub use crate::bools::{deserialize_bools, serialize_bools};
+    #[cfg(feature = "serde")]
+    pub use serde;
+
+    pub trait SubsetFlagMatches {
+        type X;
+    }
+    impl SubsetFlagMatches for AssertionSucceeded {
+        type X = ();
+    }
+
+    pub use enumflags2_derive::translate_flags_internal;
+
+    pub trait TranslationIsOneToOne {
+        type X;
+    }
+    impl TranslationIsOneToOne for AssertionSucceeded {
+        type X = ();
+    }
+
+    pub trait TranslationCoversAllFlags {
+        type X;
+    }
+    impl TranslationCoversAllFlags for AssertionSucceeded {
+        type X = ();
+    }
+
+    pub trait MatchArmIsReachable {
+        type X;
+    }
+    impl MatchArmIsReachable for AssertionSucceeded {
+        type X = ();
+    }
+
+    pub trait MatchIsExhaustive {
+        type X;
+    }
+    impl MatchIsExhaustive for AssertionSucceeded {
+        type X = ();
+    }
+
+    /// An arm of `bitflags_match!`, with the masks widened to `u128`.
+    #[derive(Clone, Copy)]
+    pub enum MatchArm {
+        Exact(u128),
+        Contains(u128),
+        Any(u128),
+        Wildcard,
+    }
+
+    impl MatchArm {
+        const fn matches(self, bits: u128) -> bool {
+            match self {
+                MatchArm::Exact(mask) => bits == mask,
+                MatchArm::Contains(mask) => bits & mask == mask,
+                MatchArm::Any(mask) => bits & mask != 0,
+                MatchArm::Wildcard => true,
+            }
+        }
+    }
+
+    /// Returns true if some subset of `all` is matched by `arms[target]`,
+    /// but none of the arms before it. If `target == arms.len()`, checks
+    /// whether some subset isn't matched by any of the arms.
+    pub const fn match_arm_reachable(arms: &[MatchArm], target: usize, all: u128) -> bool {
+        if target < arms.len() {
+            if let MatchArm::Exact(mask) = arms[target] {
+                let mut i = 0;
+                while i < target {
+                    if arms[i].matches(mask) {
+                        return false;
+                    }
+                    i += 1;
+                }
+                return true;
+            }
+        }
+
+        find_unmatched(arms, target, 0, 0, !all)
+    }
+
+    /// Search for a set of flags that contains `required`, avoids `forbidden`,
+    /// doesn't match any of the arms in `i..target`, and matches `arms[target]`.
+    /// Arms that require some of several flags are handled by trying each
+    /// of those flags in turn.
+    const fn find_unmatched(
+        arms: &[MatchArm],
+        target: usize,
+        i: usize,
+        required: u128,
+        forbidden: u128,
+    ) -> bool {
+        if required & forbidden != 0 {
+            return false;
+        }
+
+        if i > target || i == arms.len() {
+            return avoids_exact(arms, target, required, forbidden);
+        }
+
+        let next = i + 1;
+        match (arms[i], i == target) {
+            (MatchArm::Exact(_), _) => find_unmatched(arms, target, next, required, forbidden),
+            (MatchArm::Wildcard, is_target) => {
+                is_target && find_unmatched(arms, target, next, required, forbidden)
+            }
+            (MatchArm::Any(mask), false) => {
+                find_unmatched(arms, target, next, required, forbidden | mask)
+            }
+            (MatchArm::Contains(mask), true) => {
+                find_unmatched(arms, target, next, required | mask, forbidden)
+            }
+            (MatchArm::Contains(mask), false) => {
+                if mask & forbidden != 0 {
+                    return find_unmatched(arms, target, next, required, forbidden);
+                }
+
+                let mut choices = mask & !required;
+                while choices != 0 {
+                    let bit = choices & choices.wrapping_neg();
+                    if find_unmatched(arms, target, next, required, forbidden | bit) {
+                        return true;
+                    }
+                    choices &= choices - 1;
+                }
+                false
+            }
+            (MatchArm::Any(mask), true) => {
+                if mask & required != 0 {
+                    return find_unmatched(arms, target, next, required, forbidden);
+                }
+
+                let mut choices = mask & !forbidden;
+                while choices != 0 {
+                    let bit = choices & choices.wrapping_neg();
+                    if find_unmatched(arms, target, next, required | bit, forbidden) {
+                        return true;
+                    }
+                    choices &= choices - 1;
+                }
+                false
+            }
+        }
+    }
+
+    /// Returns true if some set of flags between `required` and `!forbidden`
+    /// isn't matched by any of the `exact` arms before `target`.
+    const fn avoids_exact(arms: &[MatchArm], target: usize, required: u128, forbidden: u128) -> bool {
+        let free = (!(required | forbidden)).count_ones();
+        if free >= 64 {
+            return true;
+        }
+
+        let mut excluded = 0;
+        let mut i = 0;
+        while i < target && i < arms.len() {
+            if let MatchArm::Exact(mask) = arms[i] {
+                let mut duplicate = false;
+                let mut j = 0;
+                while j < i {
+                    if let MatchArm::Exact(other) = arms[j] {
+                        duplicate |= other == mask;
+                    }
+                    j += 1;
+                }
+
+                if !duplicate && mask & required == required && mask & forbidden == 0 {
+                    excluded += 1;
+                }
+            }
+            i += 1;
+        }
+
+        excluded < 1u128 << free
+    }
+
+    #[cold]
+    pub fn match_unreachable() -> ! {
+        panic!("bitflags_match!: no arm matched")
+    }
 }
 
 use _internal::BitFlagNum;
 
 // Internal debug formatting implementations
 mod formatting;
+pub use crate::formatting::{DisplayFlags, ParseFlagError};
+#[cfg(feature = "serde")]
+pub use crate::formatting::{deserialize_names, serialize_names};
 
 // impl TryFrom<T::Numeric> for BitFlags<T>
 mod fallible;
-pub use crate::fallible::FromBitsError;
+pub use crate::fallible::{FromBitsError, MissingFlags, UnexpectedFlags};
 
 mod iter;
 pub use crate::iter::Iter;
 
+mod subsets;
+pub use crate::subsets::{Combinations, GraySubsets, Subsets, Supersets};
+
+mod patch;
+pub use crate::patch::{FlagsPatch, ParsePatchError};
+
+mod symbolic;
+pub use crate::symbolic::{ParseSymbolicError, PlaceholderError, Symbolic};
+
+mod explain;
+pub use crate::explain::ExplainFlags;
+
+// Support for the structs generated by #[bitflags(bools)]
+mod bools;
+
+mod layers;
+pub use crate::layers::{Decisions, FlagLayers, Provenance};
+
+mod pattern;
+pub use crate::pattern::{FlagPattern, RulePairs, RuleTable};
+
+mod function;
+pub use crate::function::{FlagFunction, Satisfying, MAX_FUNCTION_FLAGS};
+
+mod projection;
+pub use crate::projection::SubsetOf;
+
+mod translate;
+pub use crate::translate::TranslateFlags;
+
+mod layout;
+pub use crate::layout::FlagLayout;
+#[cfg(feature = "std")]
+pub use crate::layout::{LayoutChange, LayoutDiff, SavedLayout};
+
+mod versioned;
+#[cfg(feature = "serde")]
+pub use crate::versioned::{deserialize_from_version, FromVersion};
+
+mod capabilities;
+pub use crate::capabilities::Capabilities;
+
+mod non_empty;
+pub use crate::non_empty::{EmptyFlagsError, NonEmptyBitFlags, SingleFlagError};
+
+#[cfg(feature = "std")]
+mod predicate;
+#[cfg(feature = "std")]
+pub use crate::predicate::{ParseExprError, Predicate, MAX_PREDICATE_DEPTH, MAX_PREDICATE_TERMS};
+
 mod const_api;
 pub use crate::const_api::ConstToken;
 
+mod const_flags;
+pub use crate::const_flags::ConstFlags;
+
+#[cfg(feature = "rayon")]
+mod parallel;
+#[cfg(feature = "rayon")]
+pub use crate::parallel::ParallelBitFlagsIterator;
+
 /// Represents a set of flags of some type `T`.
 /// `T` must have the `#[bitflags]` attribute applied.
 ///
@@ -559,9 +1230,32 @@ pub struct BitFlags<T, N = <T as _internal::RawBitFlags>::Numeric> {
 ///
 /// // Also works in const contexts:
 /// const X: BitFlags<Test> = make_bitflags!(Test::A);
+///
+/// // Adding `as u128` produces the raw bits instead, for use as the
+/// // parameter of `ConstFlags`:
+/// const BITS: u128 = make_bitflags!(Test::{A | C} as u128);
+/// assert_eq!(BITS, 0b101);
 /// ```
 #[macro_export]
 macro_rules! make_bitflags {
+    ( $enum:ident ::{ $($variant:ident)|* } as u128 ) => {
+        {
+            let mut n = 0u128;
+            $(
+                {
+                    let flag: $enum = $enum::$variant;
+                    n |= flag as u128;
+                }
+            )*
+            n
+        }
+    };
+    ( $enum:ident :: $variant:ident as u128 ) => {
+        {
+            let flag: $enum = $enum::$variant;
+            flag as u128
+        }
+    };
     ( $enum:ident ::{ $($variant:ident)|* } ) => {
         {
             let mut n = 0;
@@ -589,6 +1283,134 @@ macro_rules! make_bitflags {
     };
 }
 
+/// `bitflags_match!` matches a set of flags against a list of arms, and
+/// evaluates the first one that applies. Each arm names some flags with
+/// [`make_bitflags!`] syntax, and one of three modes:
+///
+/// - `exact` matches when exactly the given flags are set,
+/// - `contains` matches when all of the given flags are set, possibly
+///   along with others,
+/// - `any` matches when at least one of the given flags is set.
+///
+/// An arm can also be `_`, which matches anything. The arms are compiled
+/// into comparisons on the underlying integer.
+///
+/// ```
+/// # use enumflags2::{bitflags, bitflags_match, BitFlags};
+/// #[bitflags]
+/// #[repr(u8)]
+/// #[derive(Clone, Copy, Debug)]
+/// enum Perm {
+///     Read = 1 << 0,
+///     Write = 1 << 1,
+///     Exec = 1 << 2,
+/// }
+///
+/// fn describe(perms: BitFlags<Perm>) -> &'static str {
+///     bitflags_match!(perms, {
+///         exact Perm::{} => "none",
+///         contains Perm::{Read | Write} => "read-write",
+///         any Perm::{Write | Exec} => "unusual",
+///         exact Perm::Read => "read-only",
+///     })
+/// }
+///
+/// assert_eq!(describe(Perm::Read | Perm::Write | Perm::Exec), "read-write");
+/// assert_eq!(describe(Perm::Read.into()), "read-only");
+/// ```
+///
+/// Every arm must be reachable: an arm that only matches flags that are
+/// handled by the preceding arms is a compile-time error. Without a `_` arm,
+/// the arms must also cover every possible set of flags, which is checked
+/// at compile time as well.
+///
+/// ```compile_fail
+/// # use enumflags2::{bitflags, bitflags_match, BitFlags};
+/// # #[bitflags]
+/// # #[repr(u8)]
+/// # #[derive(Clone, Copy, Debug)]
+/// # enum Perm {
+/// #     Read = 1 << 0,
+/// #     Write = 1 << 1,
+/// #     Exec = 1 << 2,
+/// # }
+/// # fn describe(perms: BitFlags<Perm>) -> &'static str {
+/// bitflags_match!(perms, {
+///     exact Perm::{} => "none",
+///     contains Perm::{Read | Write} => "read-write",
+///     // error: Exec without Write isn't covered
+///     any Perm::{Write} => "unusual",
+///     exact Perm::Read => "read-only",
+/// })
+/// # }
+/// ```
+#[macro_export]
+macro_rules! bitflags_match {
+    ( $flags:expr, { $($arms:tt)* } ) => {
+        $crate::__bitflags_match!(@arms ($flags) [] $($arms)*)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __bitflags_match {
+    (@arms $flags:tt [$($done:tt)*]) => {
+        $crate::_internal::bitflags_match_internal!($flags $($done)*)
+    };
+    (@arms $flags:tt [$($done:tt)*] _ => $($rest:tt)*) => {
+        $crate::__bitflags_match!(@body $flags [$($done)*] [_] $($rest)*)
+    };
+    (@arms $flags:tt [$($done:tt)*] $mode:ident $enum:ident :: $variants:tt => $($rest:tt)*) => {
+        $crate::__bitflags_match!(@body $flags [$($done)*] [$mode $enum :: $variants] $($rest)*)
+    };
+    (@body $flags:tt [$($done:tt)*] $pattern:tt $body:expr, $($rest:tt)*) => {
+        $crate::__bitflags_match!(@arms $flags [$($done)* $pattern ($body)] $($rest)*)
+    };
+    (@body $flags:tt [$($done:tt)*] $pattern:tt $body:block $($rest:tt)*) => {
+        $crate::__bitflags_match!(@arms $flags [$($done)* $pattern ($body)] $($rest)*)
+    };
+    (@body $flags:tt [$($done:tt)*] $pattern:tt $body:expr) => {
+        $crate::__bitflags_match!(@arms $flags [$($done)* $pattern ($body)])
+    };
+}
+
+/// `flag_expr!` turns a boolean expression over flag names into a closure
+/// that evaluates it for a `BitFlags<T>`.
+///
+/// Flag names can be combined with `!`, `&`, `^` and `|`, in order of
+/// decreasing precedence, and grouped with parentheses. Each flag name
+/// stands for "this flag is set". Since the names are resolved by the
+/// compiler, a typo is a compile-time error.
+///
+/// The result is a closure that doesn't capture anything, so it can be
+/// coerced into a `fn(BitFlags<T>) -> bool`. To parse the same syntax
+/// at runtime, use `Predicate` (requires the `std` feature).
+///
+/// ```
+/// # use enumflags2::{bitflags, flag_expr, BitFlags};
+/// #[bitflags]
+/// #[repr(u8)]
+/// #[derive(Clone, Copy, Debug)]
+/// enum Role {
+///     Admin = 1 << 0,
+///     Editor = 1 << 1,
+///     ReadOnly = 1 << 2,
+/// }
+///
+/// const CAN_EDIT: fn(BitFlags<Role>) -> bool =
+///     flag_expr!(Role, Admin | (Editor & !ReadOnly));
+///
+/// assert!(CAN_EDIT(Role::Admin | Role::ReadOnly));
+/// assert!(CAN_EDIT(Role::Editor.into()));
+/// assert!(!CAN_EDIT(Role::Editor | Role::ReadOnly));
+/// ```
+#[macro_export]
+macro_rules! flag_expr {
+    ( $($input:tt)* ) => {
+        $crate::_internal::flag_expr_internal!($($input)*)
+    };
+}
+
 /// The default value returned is one with all flags unset, i. e. [`empty`][Self::empty],
 /// unless [customized](index.html#customizing-default).
 impl<T> Default for BitFlags<T>
@@ -611,6 +1433,17 @@ impl<T: BitFlag> From<T> for BitFlags<T> {
     }
 }
 
+/// Lets `FromIterator`, `Extend` and `Sum` take iterators over `&T`.
+/// Separate impls for `&T` would overlap with the ones for
+/// `B: Into<BitFlags<T>>`, so this also lets `contains`, `insert`, `|` and
+/// the other methods taking `impl Into<BitFlags<T>>` accept `&T`.
+impl<T: BitFlag> From<&T> for BitFlags<T> {
+    #[inline(always)]
+    fn from(t: &T) -> BitFlags<T> {
+        Self::from_flag(*t)
+    }
+}
+
 impl<T> BitFlags<T>
 where
     T: BitFlag,
@@ -648,6 +1481,7 @@ where
             Err(FromBitsError {
                 flags,
                 invalid: bits & !flags.bits(),
+                legacy: versioned::legacy_layout::<T>(bits),
             })
         }
     }
@@ -0,0 +1,329 @@
+use crate::{BitFlag, BitFlagNum, BitFlags, Iter};
+use core::convert::TryFrom;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::{cmp, fmt, ops};
+
+/// A set of flags with at least one flag set.
+///
+/// The value is stored in a `NonZero` integer of the same width as `T`,
+/// which lets the compiler use zero to represent `None`. Thus,
+/// `Option<NonEmptyBitFlags<T>>` takes no more space than `BitFlags<T>`.
+///
+/// ```
+/// # use core::convert::TryFrom;
+/// # use core::mem::size_of;
+/// # use enumflags2::{bitflags, BitFlags, NonEmptyBitFlags};
+/// #[bitflags]
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// #[repr(u16)]
+/// enum Channel {
+///     Email = 1 << 0,
+///     Sms = 1 << 1,
+///     Push = 1 << 2,
+/// }
+///
+/// assert_eq!(size_of::<Option<NonEmptyBitFlags<Channel>>>(), size_of::<u16>());
+///
+/// fn notify(channels: NonEmptyBitFlags<Channel>) -> Channel {
+///     // There is always a first channel to try.
+///     channels.first()
+/// }
+///
+/// let channels = NonEmptyBitFlags::try_from(Channel::Sms | Channel::Push).unwrap();
+/// assert_eq!(notify(channels), Channel::Sms);
+/// assert!(NonEmptyBitFlags::<Channel>::new(BitFlags::empty()).is_none());
+/// ```
+///
+/// For exactly one flag, use `T` itself: `#[bitflags(try_from)]` implements
+/// `TryFrom<BitFlags<T>>` for it, based on [`BitFlags::exactly_one`].
+///
+/// ```
+/// # use core::convert::TryFrom;
+/// # use enumflags2::{bitflags, BitFlags};
+/// # #[bitflags(try_from)]
+/// # #[derive(Clone, Copy, Debug, PartialEq)]
+/// # #[repr(u16)]
+/// # enum Channel {
+/// #     Email = 1 << 0,
+/// #     Sms = 1 << 1,
+/// #     Push = 1 << 2,
+/// # }
+/// assert_eq!(Channel::try_from(BitFlags::from(Channel::Sms)), Ok(Channel::Sms));
+/// assert!(Channel::try_from(Channel::Sms | Channel::Push).is_err());
+/// ```
+#[repr(transparent)]
+pub struct NonEmptyBitFlags<T: BitFlag> {
+    val: <T::Numeric as BitFlagNum>::NonZero,
+    marker: PhantomData<T>,
+}
+
+impl<T> NonEmptyBitFlags<T>
+where
+    T: BitFlag,
+{
+    /// Returns `None` if no flags are set.
+    #[inline]
+    pub fn new<B: Into<BitFlags<T>>>(flags: B) -> Option<Self> {
+        flags.into().bits().to_non_zero().map(|val| NonEmptyBitFlags {
+            val,
+            marker: PhantomData,
+        })
+    }
+
+    /// Create a set with a single flag.
+    #[inline]
+    pub fn from_flag(flag: T) -> Self {
+        BitFlags::from_flag(flag).into_non_empty()
+    }
+
+    /// Create a set with all the flags of `T`.
+    #[inline]
+    pub fn all() -> Self {
+        BitFlags::all().into_non_empty()
+    }
+
+    /// Return the flags as a `BitFlags<T>`.
+    #[inline(always)]
+    pub fn flags(self) -> BitFlags<T> {
+        // SAFETY: The value was created from a `BitFlags<T>`.
+        unsafe { BitFlags::from_bits_unchecked(T::Numeric::from_non_zero(self.val)) }
+    }
+
+    /// Returns the underlying bitwise value.
+    #[inline(always)]
+    pub fn bits(self) -> T::Numeric {
+        T::Numeric::from_non_zero(self.val)
+    }
+
+    /// Returns true if all flags are set.
+    #[inline(always)]
+    pub fn is_all(self) -> bool {
+        self.flags().is_all()
+    }
+
+    /// Always returns false. Provided for parity with [`BitFlags::is_empty`].
+    #[inline(always)]
+    pub fn is_empty(self) -> bool {
+        false
+    }
+
+    /// Returns the number of flags set. Always at least one.
+    #[inline(always)]
+    pub fn len(self) -> usize {
+        self.flags().len()
+    }
+
+    /// If exactly one flag is set, the flag is returned. Otherwise, returns `None`.
+    #[inline(always)]
+    pub fn exactly_one(self) -> Option<T> {
+        self.flags().exactly_one()
+    }
+
+    /// Returns true if all of the flags in `other` are set.
+    #[inline(always)]
+    pub fn contains<B: Into<BitFlags<T>>>(self, other: B) -> bool {
+        self.flags().contains(other)
+    }
+
+    /// Returns true if at least one flag is shared.
+    #[inline(always)]
+    pub fn intersects<B: Into<BitFlags<T>>>(self, other: B) -> bool {
+        self.flags().intersects(other)
+    }
+
+    /// Inserts the flags into the set. The set stays non-empty.
+    #[inline(always)]
+    pub fn insert<B: Into<BitFlags<T>>>(&mut self, other: B) {
+        *self = *self | other;
+    }
+
+    /// Returns the flag with the lowest value.
+    #[inline]
+    pub fn first(self) -> T {
+        self.split_first().0
+    }
+
+    /// Returns the first flag, and an iterator over the remaining ones.
+    #[inline]
+    pub fn split_first(self) -> (T, Iter<T>) {
+        let mut iter = self.iter();
+        let first = iter.next().expect("NonEmptyBitFlags is never empty");
+        (first, iter)
+    }
+
+    /// Iterate over the flags. The iterator yields at least one flag.
+    #[inline]
+    pub fn iter(self) -> Iter<T> {
+        self.flags().iter()
+    }
+}
+
+impl<T: BitFlag> BitFlags<T> {
+    /// Convert a set that is known to be non-empty, e.g. one that was
+    /// just built from a flag.
+    fn into_non_empty(self) -> NonEmptyBitFlags<T> {
+        NonEmptyBitFlags::new(self).expect("the set is not empty")
+    }
+}
+
+impl<T: BitFlag> From<T> for NonEmptyBitFlags<T> {
+    #[inline(always)]
+    fn from(flag: T) -> Self {
+        Self::from_flag(flag)
+    }
+}
+
+impl<T: BitFlag> From<NonEmptyBitFlags<T>> for BitFlags<T> {
+    #[inline(always)]
+    fn from(flags: NonEmptyBitFlags<T>) -> Self {
+        flags.flags()
+    }
+}
+
+impl<T: BitFlag> TryFrom<BitFlags<T>> for NonEmptyBitFlags<T> {
+    type Error = EmptyFlagsError;
+
+    #[inline]
+    fn try_from(flags: BitFlags<T>) -> Result<Self, Self::Error> {
+        Self::new(flags).ok_or(EmptyFlagsError)
+    }
+}
+
+impl<T, B> ops::BitOr<B> for NonEmptyBitFlags<T>
+where
+    T: BitFlag,
+    B: Into<BitFlags<T>>,
+{
+    type Output = Self;
+    #[inline(always)]
+    fn bitor(self, other: B) -> Self {
+        (self.flags() | other.into()).into_non_empty()
+    }
+}
+
+impl<T: BitFlag> IntoIterator for NonEmptyBitFlags<T> {
+    type IntoIter = Iter<T>;
+    type Item = T;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: BitFlag> Copy for NonEmptyBitFlags<T> {}
+
+impl<T: BitFlag> Clone for NonEmptyBitFlags<T> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: BitFlag> PartialEq for NonEmptyBitFlags<T> {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.val == other.val
+    }
+}
+
+impl<T: BitFlag> Eq for NonEmptyBitFlags<T> {}
+
+impl<T: BitFlag> PartialEq<BitFlags<T>> for NonEmptyBitFlags<T> {
+    #[inline(always)]
+    fn eq(&self, other: &BitFlags<T>) -> bool {
+        self.flags() == *other
+    }
+}
+
+impl<T: BitFlag> PartialOrd for NonEmptyBitFlags<T> {
+    #[inline(always)]
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: BitFlag> Ord for NonEmptyBitFlags<T> {
+    #[inline(always)]
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.bits().cmp(&other.bits())
+    }
+}
+
+impl<T: BitFlag> Hash for NonEmptyBitFlags<T> {
+    #[inline(always)]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.val.hash(state)
+    }
+}
+
+impl<T> fmt::Debug for NonEmptyBitFlags<T>
+where
+    T: BitFlag,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.flags(), fmt)
+    }
+}
+
+impl<T> fmt::Display for NonEmptyBitFlags<T>
+where
+    T: BitFlag,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.flags(), fmt)
+    }
+}
+
+/// The error returned when converting an empty `BitFlags<T>`
+/// into a [`NonEmptyBitFlags<T>`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct EmptyFlagsError;
+
+impl fmt::Display for EmptyFlagsError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("expected at least one flag to be set")
+    }
+}
+
+#[cfg(enumflags2_core_error)]
+impl core::error::Error for EmptyFlagsError {}
+
+#[cfg(all(feature = "std", not(enumflags2_core_error)))]
+impl std::error::Error for EmptyFlagsError {}
+
+/// The error returned when converting a `BitFlags<T>` into `T` fails,
+/// because it doesn't have exactly one flag set.
+#[derive(Debug, Copy, Clone)]
+pub struct SingleFlagError<T: BitFlag> {
+    pub(crate) flags: BitFlags<T>,
+}
+
+impl<T: BitFlag> SingleFlagError<T> {
+    /// Return the flags that were being converted.
+    pub fn flags(self) -> BitFlags<T> {
+        self.flags
+    }
+}
+
+impl<T: BitFlag> PartialEq for SingleFlagError<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.flags == other.flags
+    }
+}
+
+impl<T: BitFlag> Eq for SingleFlagError<T> {}
+
+impl<T: BitFlag> fmt::Display for SingleFlagError<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "expected exactly one flag, found {}", self.flags)
+    }
+}
+
+#[cfg(enumflags2_core_error)]
+impl<T: BitFlag + fmt::Debug> core::error::Error for SingleFlagError<T> {}
+
+#[cfg(all(feature = "std", not(enumflags2_core_error)))]
+impl<T: BitFlag + fmt::Debug> std::error::Error for SingleFlagError<T> {}
@@ -0,0 +1,86 @@
+use crate::{BitFlag, BitFlags};
+use rayon::iter::{FromParallelIterator, IntoParallelIterator, ParallelExtend, ParallelIterator};
+
+impl<T, B> FromParallelIterator<B> for BitFlags<T>
+where
+    T: BitFlag + Send,
+    B: Into<BitFlags<T>> + Send,
+{
+    fn from_par_iter<I>(it: I) -> BitFlags<T>
+    where
+        I: IntoParallelIterator<Item = B>,
+    {
+        it.into_par_iter()
+            .map(Into::into)
+            .reduce(BitFlags::empty, |acc, flags| acc | flags)
+    }
+}
+
+impl<T, B> ParallelExtend<B> for BitFlags<T>
+where
+    T: BitFlag + Send,
+    B: Into<BitFlags<T>> + Send,
+{
+    fn par_extend<I>(&mut self, it: I)
+    where
+        I: IntoParallelIterator<Item = B>,
+    {
+        *self |= BitFlags::from_par_iter(it)
+    }
+}
+
+/// Reductions over parallel iterators of flags.
+///
+/// This trait is implemented for every [`ParallelIterator`] whose items
+/// can be converted into `BitFlags<T>`, such as the one returned by
+/// `par_iter` on a slice of `BitFlags<T>`.
+///
+/// ```
+/// # use enumflags2::{bitflags, BitFlags, ParallelBitFlagsIterator};
+/// use rayon::prelude::*;
+///
+/// #[bitflags]
+/// #[derive(Clone, Copy, PartialEq, Debug)]
+/// #[repr(u8)]
+/// enum MyFlag {
+///     A = 1 << 0,
+///     B = 1 << 1,
+///     C = 1 << 2,
+/// }
+///
+/// let sets = [MyFlag::A | MyFlag::B, MyFlag::B | MyFlag::C, MyFlag::B.into()];
+///
+/// let union: BitFlags<MyFlag> = sets.par_iter().copied().union();
+/// assert_eq!(union, BitFlags::all());
+///
+/// let intersection: BitFlags<MyFlag> = sets.par_iter().copied().intersection();
+/// assert_eq!(intersection, MyFlag::B);
+/// ```
+pub trait ParallelBitFlagsIterator<T: BitFlag>: ParallelIterator {
+    /// Return the flags that are set in any of the items.
+    ///
+    /// Equivalent to collecting into a `BitFlags<T>`.
+    fn union(self) -> BitFlags<T>;
+
+    /// Return the flags that are set in all of the items.
+    ///
+    /// The intersection of zero items is [`BitFlags::all`].
+    fn intersection(self) -> BitFlags<T>;
+}
+
+impl<T, I> ParallelBitFlagsIterator<T> for I
+where
+    T: BitFlag + Send,
+    I: ParallelIterator,
+    I::Item: Into<BitFlags<T>>,
+{
+    fn union(self) -> BitFlags<T> {
+        self.map(Into::into)
+            .reduce(BitFlags::empty, |acc, flags| acc | flags)
+    }
+
+    fn intersection(self) -> BitFlags<T> {
+        self.map(Into::into)
+            .reduce(BitFlags::all, |acc, flags| acc & flags)
+    }
+}
@@ -0,0 +1,443 @@
+use crate::formatting::{flag_from_name, FlagName};
+use crate::{BitFlag, BitFlags};
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::str::FromStr;
+
+/// A change to a set of flags: some flags to add, and some flags to remove.
+///
+/// A patch can be computed from two versions of a `BitFlags` with
+/// [`BitFlags::diff`], and then applied to other values with
+/// [`apply`][FlagsPatch::apply].
+///
+/// If a flag is both in `add` and `remove`, adding it takes precedence.
+///
+/// ```
+/// # use enumflags2::{bitflags, make_bitflags, BitFlags, FlagsPatch};
+/// #[bitflags]
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// #[repr(u8)]
+/// enum MyFlag {
+///     A = 1 << 0,
+///     B = 1 << 1,
+///     C = 1 << 2,
+/// }
+///
+/// let old = make_bitflags!(MyFlag::{A | B});
+/// let new = make_bitflags!(MyFlag::{B | C});
+/// let patch = old.diff(new);
+///
+/// assert_eq!(patch.add, MyFlag::C);
+/// assert_eq!(patch.remove, MyFlag::A);
+/// assert_eq!(patch.apply(old), new);
+/// assert_eq!(patch.to_string(), "+C -A");
+/// assert_eq!("+C -A".parse::<FlagsPatch<MyFlag>>().unwrap(), patch);
+/// ```
+///
+/// ## Text representation
+///
+/// The [`Display`][fmt::Display] and [`FromStr`] implementations use a
+/// whitespace-separated list of flag names, each prefixed with `+` if it is
+/// to be added, and `-` if it is to be removed. Flags are written with their
+/// canonical name, and parsed by either their name or one of their aliases
+/// (see [Renaming flags](crate#renaming-flags)). The empty patch is written
+/// as `<empty>`, though parsing also accepts an empty string.
+#[derive(Copy, Clone)]
+pub struct FlagsPatch<T: BitFlag> {
+    /// The flags to add.
+    pub add: BitFlags<T>,
+    /// The flags to remove.
+    pub remove: BitFlags<T>,
+}
+
+impl<T> BitFlags<T>
+where
+    T: BitFlag,
+{
+    /// Compute the patch that turns `self` into `new`.
+    ///
+    /// The result only adds flags that aren't in `self`, and only removes
+    /// flags that are in `self`, which makes it possible to
+    /// [`invert`][FlagsPatch::invert] it.
+    #[inline]
+    pub fn diff<B: Into<BitFlags<T>>>(self, new: B) -> FlagsPatch<T> {
+        let new = new.into();
+        FlagsPatch {
+            add: new & !self,
+            remove: self & !new,
+        }
+    }
+}
+
+impl<T> FlagsPatch<T>
+where
+    T: BitFlag,
+{
+    /// Create a patch that adds and removes the given flags.
+    #[inline]
+    pub fn new<A, R>(add: A, remove: R) -> Self
+    where
+        A: Into<BitFlags<T>>,
+        R: Into<BitFlags<T>>,
+    {
+        FlagsPatch {
+            add: add.into(),
+            remove: remove.into(),
+        }
+    }
+
+    /// A patch that doesn't change anything.
+    #[inline]
+    pub fn empty() -> Self {
+        FlagsPatch {
+            add: BitFlags::empty(),
+            remove: BitFlags::empty(),
+        }
+    }
+
+    /// Returns true if applying the patch never changes anything.
+    #[inline]
+    pub fn is_empty(self) -> bool {
+        self.add.is_empty() && self.remove.is_empty()
+    }
+
+    /// Returns what the patch does to a single flag: `Some(true)` if it
+    /// forces the flag on, `Some(false)` if it forces it off, and `None`
+    /// if the flag is left alone.
+    #[inline]
+    pub fn state(self, flag: T) -> Option<bool> {
+        if self.add.contains(flag) {
+            Some(true)
+        } else if self.remove.contains(flag) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// Apply the patch to a set of flags.
+    #[inline]
+    pub fn apply<B: Into<BitFlags<T>>>(self, flags: B) -> BitFlags<T> {
+        (flags.into() & !self.remove) | self.add
+    }
+
+    /// Combine two patches into one, which has the same effect as
+    /// applying `self`, and then `next`.
+    ///
+    /// ```
+    /// # use enumflags2::{bitflags, BitFlags, FlagsPatch};
+    /// # #[bitflags]
+    /// # #[derive(Clone, Copy, Debug, PartialEq)]
+    /// # #[repr(u8)]
+    /// # enum MyFlag {
+    /// #     A = 1 << 0,
+    /// #     B = 1 << 1,
+    /// #     C = 1 << 2,
+    /// # }
+    /// let first = FlagsPatch::new(MyFlag::A | MyFlag::B, MyFlag::C);
+    /// let second = FlagsPatch::new(MyFlag::C, MyFlag::A);
+    /// let both = first.then(second);
+    ///
+    /// assert_eq!(both.add, MyFlag::B | MyFlag::C);
+    /// assert_eq!(both.remove, MyFlag::A);
+    /// ```
+    #[inline]
+    pub fn then(self, next: Self) -> Self {
+        let add = next.add | (self.add & !next.remove);
+        FlagsPatch {
+            add,
+            remove: (self.remove | next.remove) & !add,
+        }
+    }
+
+    /// Return the patch that undoes this one.
+    ///
+    /// This swaps the added and removed flags. Undoing the patch is only
+    /// possible if it didn't add flags that were already set, nor remove
+    /// flags that weren't set – which is always true for the result
+    /// of [`BitFlags::diff`].
+    ///
+    /// ```
+    /// # use enumflags2::{bitflags, make_bitflags};
+    /// # #[bitflags]
+    /// # #[derive(Clone, Copy, Debug, PartialEq)]
+    /// # #[repr(u8)]
+    /// # enum MyFlag {
+    /// #     A = 1 << 0,
+    /// #     B = 1 << 1,
+    /// #     C = 1 << 2,
+    /// # }
+    /// let old = make_bitflags!(MyFlag::{A | B});
+    /// let patch = old.diff(MyFlag::C);
+    /// assert_eq!(patch.invert().apply(patch.apply(old)), old);
+    /// ```
+    #[inline]
+    pub fn invert(self) -> Self {
+        FlagsPatch {
+            add: self.remove,
+            remove: self.add,
+        }
+    }
+}
+
+/// The default value is the [empty patch][FlagsPatch::empty].
+impl<T: BitFlag> Default for FlagsPatch<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<T: BitFlag> PartialEq for FlagsPatch<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.add == other.add && self.remove == other.remove
+    }
+}
+
+impl<T: BitFlag> Eq for FlagsPatch<T> {}
+
+impl<T: BitFlag> Hash for FlagsPatch<T> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.add.hash(state);
+        self.remove.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for FlagsPatch<T>
+where
+    T: BitFlag,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("FlagsPatch")
+            .field("add", &self.add)
+            .field("remove", &self.remove)
+            .finish()
+    }
+}
+
+impl<T> fmt::Display for FlagsPatch<T>
+where
+    T: BitFlag,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return fmt.write_str("<empty>");
+        }
+
+        let added = self.add.iter().map(|flag| ('+', flag));
+        let removed = self.remove.iter().map(|flag| ('-', flag));
+        for (i, (sign, flag)) in added.chain(removed).enumerate() {
+            if i != 0 {
+                fmt.write_str(" ")?;
+            }
+            write!(fmt, "{}{:?}", sign, FlagName(flag))?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> FromStr for FlagsPatch<T>
+where
+    T: BitFlag,
+{
+    type Err = ParsePatchError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut patch = FlagsPatch::empty();
+        if s.trim() == "<empty>" {
+            return Ok(patch);
+        }
+
+        let mut rest = s;
+        loop {
+            rest = rest.trim_start();
+            if rest.is_empty() {
+                return Ok(patch);
+            }
+
+            let position = s.len() - rest.len();
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            let (token, tail) = rest.split_at(end);
+            rest = tail;
+
+            let add = match token.as_bytes()[0] {
+                b'+' => true,
+                b'-' => false,
+                _ => return Err(ParsePatchError {
+                    position,
+                    kind: ParsePatchErrorKind::MissingSign,
+                }),
+            };
+
+            let flag: T = flag_from_name(&token[1..]).ok_or(ParsePatchError {
+                position: position + 1,
+                kind: ParsePatchErrorKind::UnknownFlag,
+            })?;
+
+            if add {
+                patch.add.insert(flag);
+            } else {
+                patch.remove.insert(flag);
+            }
+        }
+    }
+}
+
+/// The error returned when parsing a [`FlagsPatch`] fails.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ParsePatchError {
+    position: usize,
+    kind: ParsePatchErrorKind,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum ParsePatchErrorKind {
+    MissingSign,
+    UnknownFlag,
+}
+
+impl ParsePatchError {
+    /// Return the byte offset into the input at which the error occurred.
+    pub fn position(self) -> usize {
+        self.position
+    }
+}
+
+impl fmt::Display for ParsePatchError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ParsePatchErrorKind::MissingSign => write!(
+                fmt,
+                "expected `+` or `-` at position {}",
+                self.position
+            ),
+            ParsePatchErrorKind::UnknownFlag => write!(
+                fmt,
+                "unknown flag name at position {}",
+                self.position
+            ),
+        }
+    }
+}
+
+#[cfg(enumflags2_core_error)]
+impl core::error::Error for ParsePatchError {}
+
+#[cfg(all(feature = "std", not(enumflags2_core_error)))]
+impl std::error::Error for ParsePatchError {}
+
+#[cfg(feature = "serde")]
+mod impl_serde {
+    use super::FlagsPatch;
+    use crate::{BitFlag, BitFlags};
+    use core::fmt;
+    use core::marker::PhantomData;
+    use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+    use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+    const FIELDS: &[&str] = &["add", "remove"];
+
+    impl<T> Serialize for FlagsPatch<T>
+    where
+        T: BitFlag,
+        T::Numeric: Serialize,
+    {
+        fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+            let mut patch = s.serialize_struct("FlagsPatch", 2)?;
+            patch.serialize_field("add", &self.add)?;
+            patch.serialize_field("remove", &self.remove)?;
+            patch.end()
+        }
+    }
+
+    impl<'de, T> Deserialize<'de> for FlagsPatch<T>
+    where
+        T: BitFlag,
+        T::Numeric: Deserialize<'de> + Into<u64>,
+    {
+        fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+            d.deserialize_struct("FlagsPatch", FIELDS, PatchVisitor(PhantomData))
+        }
+    }
+
+    enum Field {
+        Add,
+        Remove,
+    }
+
+    impl<'de> Deserialize<'de> for Field {
+        fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+            struct FieldVisitor;
+
+            impl<'de> Visitor<'de> for FieldVisitor {
+                type Value = Field;
+
+                fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    fmt.write_str("`add` or `remove`")
+                }
+
+                fn visit_str<E: de::Error>(self, value: &str) -> Result<Field, E> {
+                    match value {
+                        "add" => Ok(Field::Add),
+                        "remove" => Ok(Field::Remove),
+                        _ => Err(E::unknown_field(value, FIELDS)),
+                    }
+                }
+            }
+
+            d.deserialize_identifier(FieldVisitor)
+        }
+    }
+
+    struct PatchVisitor<T>(PhantomData<T>);
+
+    impl<'de, T> Visitor<'de> for PatchVisitor<T>
+    where
+        T: BitFlag,
+        T::Numeric: Deserialize<'de> + Into<u64>,
+    {
+        type Value = FlagsPatch<T>;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt.write_str("struct FlagsPatch")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let add: BitFlags<T> = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+            let remove: BitFlags<T> = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+            Ok(FlagsPatch { add, remove })
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut add = None;
+            let mut remove = None;
+            while let Some(key) = map.next_key()? {
+                match key {
+                    Field::Add => {
+                        if add.is_some() {
+                            return Err(de::Error::duplicate_field("add"));
+                        }
+                        add = Some(map.next_value()?);
+                    }
+                    Field::Remove => {
+                        if remove.is_some() {
+                            return Err(de::Error::duplicate_field("remove"));
+                        }
+                        remove = Some(map.next_value()?);
+                    }
+                }
+            }
+
+            Ok(FlagsPatch {
+                add: add.ok_or_else(|| de::Error::missing_field("add"))?,
+                remove: remove.ok_or_else(|| de::Error::missing_field("remove"))?,
+            })
+        }
+    }
+}
@@ -0,0 +1,338 @@
+use crate::{BitFlag, BitFlags};
+use core::fmt;
+use core::hash::{Hash, Hasher};
+
+/// A pattern that matches sets of flags: it requires some flags to be set,
+/// and forbids some others. All the remaining flags are ignored.
+///
+/// The [`flag_pattern!`](crate::flag_pattern) macro provides a succinct syntax for creating
+/// patterns, which also works in `const` contexts.
+///
+/// ```
+/// # use enumflags2::{bitflags, flag_pattern, FlagPattern};
+/// #[bitflags]
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// #[repr(u8)]
+/// enum Packet {
+///     Syn = 1 << 0,
+///     Ack = 1 << 1,
+///     Rst = 1 << 2,
+/// }
+///
+/// const SYN_ONLY: FlagPattern<Packet> = flag_pattern!(Packet::{Syn & !Ack});
+///
+/// assert!(SYN_ONLY.matches(Packet::Syn));
+/// assert!(SYN_ONLY.matches(Packet::Syn | Packet::Rst));
+/// assert!(!SYN_ONLY.matches(Packet::Syn | Packet::Ack));
+/// ```
+///
+/// Like [`BitFlags`], `FlagPattern` has a second type parameter to make it
+/// usable from `const fn`s. It never needs to be specified manually.
+#[derive(Copy, Clone)]
+pub struct FlagPattern<T, N = <T as crate::_internal::RawBitFlags>::Numeric> {
+    /// The flags that must be set.
+    pub required: BitFlags<T, N>,
+    /// The flags that must not be set.
+    pub forbidden: BitFlags<T, N>,
+}
+
+impl<T> FlagPattern<T>
+where
+    T: BitFlag,
+{
+    /// Create a pattern from the flags that must be set, and the flags that
+    /// must not be set.
+    #[inline]
+    pub fn new<R, F>(required: R, forbidden: F) -> Self
+    where
+        R: Into<BitFlags<T>>,
+        F: Into<BitFlags<T>>,
+    {
+        FlagPattern {
+            required: required.into(),
+            forbidden: forbidden.into(),
+        }
+    }
+
+    /// A pattern that matches any set of flags.
+    #[inline]
+    pub fn any() -> Self {
+        FlagPattern {
+            required: BitFlags::empty(),
+            forbidden: BitFlags::empty(),
+        }
+    }
+
+    /// Returns true if the flags match the pattern.
+    #[inline]
+    pub fn matches<B: Into<BitFlags<T>>>(self, flags: B) -> bool {
+        let flags = flags.into();
+        flags.contains(self.required) && !flags.intersects(self.forbidden)
+    }
+
+    /// Returns false if the pattern can never match, because some flag
+    /// is both required and forbidden.
+    #[inline]
+    pub fn is_satisfiable(self) -> bool {
+        !self.required.intersects(self.forbidden)
+    }
+
+    /// Returns true if some set of flags matches both patterns.
+    #[inline]
+    pub fn overlaps(self, other: Self) -> bool {
+        self.is_satisfiable()
+            && other.is_satisfiable()
+            && !self.required.intersects(other.forbidden)
+            && !other.required.intersects(self.forbidden)
+    }
+
+    /// Returns true if every set of flags that matches `other` also matches `self`.
+    #[inline]
+    pub fn covers(self, other: Self) -> bool {
+        !other.is_satisfiable()
+            || (other.required.contains(self.required) && other.forbidden.contains(self.forbidden))
+    }
+}
+
+impl<T, N: PartialEq> PartialEq for FlagPattern<T, N> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.required == other.required && self.forbidden == other.forbidden
+    }
+}
+
+impl<T, N: Eq> Eq for FlagPattern<T, N> {}
+
+impl<T, N: Hash> Hash for FlagPattern<T, N> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.required.hash(state);
+        self.forbidden.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for FlagPattern<T>
+where
+    T: BitFlag,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("FlagPattern")
+            .field("required", &self.required)
+            .field("forbidden", &self.forbidden)
+            .finish()
+    }
+}
+
+/// A list of rules, each associating a [`FlagPattern`] with a value.
+///
+/// Looking up a set of flags returns the value of the first rule whose
+/// pattern matches. A table can be built in a `const` context:
+///
+/// ```
+/// # use enumflags2::{bitflags, flag_pattern, BitFlags, FlagPattern, RuleTable};
+/// #[bitflags]
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// #[repr(u8)]
+/// enum Packet {
+///     Syn = 1 << 0,
+///     Ack = 1 << 1,
+///     Rst = 1 << 2,
+/// }
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Action {
+///     Drop,
+///     Accept,
+/// }
+///
+/// const RULES: &[(FlagPattern<Packet>, Action)] = &[
+///     (flag_pattern!(Packet::{Rst}), Action::Drop),
+///     (flag_pattern!(Packet::{Syn & !Ack}), Action::Accept),
+///     (flag_pattern!(Packet::{Ack}), Action::Accept),
+///     (flag_pattern!(Packet::{Syn & Ack & Rst}), Action::Accept),
+/// ];
+/// const FIREWALL: RuleTable<Packet, Action> = RuleTable::new(RULES);
+///
+/// assert_eq!(FIREWALL.classify(Packet::Syn), Some(&Action::Accept));
+/// assert_eq!(FIREWALL.classify(Packet::Syn | Packet::Rst), Some(&Action::Drop));
+/// assert_eq!(FIREWALL.classify(Packet::Syn | Packet::Ack), Some(&Action::Accept));
+/// assert_eq!(FIREWALL.classify(BitFlags::empty()), None);
+///
+/// // The last rule can never be reached: all the packets it matches
+/// // are already handled by the first and the third rule.
+/// assert_eq!(FIREWALL.shadowed().collect::<Vec<_>>(), [(3, 0), (3, 2)]);
+/// ```
+pub struct RuleTable<'a, T, V, N = <T as crate::_internal::RawBitFlags>::Numeric> {
+    rules: &'a [(FlagPattern<T, N>, V)],
+}
+
+impl<'a, T, V, N> RuleTable<'a, T, V, N> {
+    /// Create a rule table. Earlier rules take precedence over later ones.
+    #[inline(always)]
+    pub const fn new(rules: &'a [(FlagPattern<T, N>, V)]) -> Self {
+        RuleTable { rules }
+    }
+
+    /// Return the rules, in order of precedence.
+    #[inline(always)]
+    pub const fn rules(&self) -> &'a [(FlagPattern<T, N>, V)] {
+        self.rules
+    }
+}
+
+impl<'a, T, V> RuleTable<'a, T, V>
+where
+    T: BitFlag,
+{
+    /// Return the index of the first rule that matches the flags.
+    #[inline]
+    pub fn position<B: Into<BitFlags<T>>>(&self, flags: B) -> Option<usize> {
+        let flags = flags.into();
+        self.rules
+            .iter()
+            .position(|(pattern, _)| pattern.matches(flags))
+    }
+
+    /// Return the value of the first rule that matches the flags.
+    #[inline]
+    pub fn classify<B: Into<BitFlags<T>>>(&self, flags: B) -> Option<&'a V> {
+        let rules = self.rules;
+        self.position(flags).map(|i| &rules[i].1)
+    }
+
+    /// Iterate over the pairs of rules `(later, earlier)`, such that
+    /// `earlier` matches everything `later` does. The later rule of such
+    /// a pair can never be selected.
+    ///
+    /// This does not detect rules that are shadowed by a combination
+    /// of several earlier rules.
+    #[inline]
+    pub fn shadowed(&self) -> RulePairs<'a, T, V> {
+        RulePairs::new(self.rules, |later, earlier| earlier.covers(later))
+    }
+
+    /// Iterate over the pairs of rules `(later, earlier)` that can both
+    /// match the same set of flags, making their relative order significant.
+    #[inline]
+    pub fn overlaps(&self) -> RulePairs<'a, T, V> {
+        RulePairs::new(self.rules, |later, earlier| earlier.overlaps(later))
+    }
+}
+
+impl<T, V, N> Copy for RuleTable<'_, T, V, N> {}
+
+impl<T, V, N> Clone for RuleTable<'_, T, V, N> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, V> fmt::Debug for RuleTable<'_, T, V>
+where
+    T: BitFlag,
+    V: fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_list().entries(self.rules.iter()).finish()
+    }
+}
+
+/// Iterator over pairs of rules in a [`RuleTable`], created by
+/// [`RuleTable::shadowed`] and [`RuleTable::overlaps`].
+pub struct RulePairs<'a, T: BitFlag, V> {
+    rules: &'a [(FlagPattern<T>, V)],
+    later: usize,
+    earlier: usize,
+    related: fn(FlagPattern<T>, FlagPattern<T>) -> bool,
+}
+
+impl<'a, T: BitFlag, V> RulePairs<'a, T, V> {
+    fn new(
+        rules: &'a [(FlagPattern<T>, V)],
+        related: fn(FlagPattern<T>, FlagPattern<T>) -> bool,
+    ) -> Self {
+        RulePairs {
+            rules,
+            later: 1,
+            earlier: 0,
+            related,
+        }
+    }
+}
+
+impl<T: BitFlag, V> Iterator for RulePairs<'_, T, V> {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.later < self.rules.len() {
+            let (later, earlier) = (self.later, self.earlier);
+            self.earlier += 1;
+            if self.earlier == self.later {
+                self.later += 1;
+                self.earlier = 0;
+            }
+
+            if (self.related)(self.rules[later].0, self.rules[earlier].0) {
+                return Some((later, earlier));
+            }
+        }
+        None
+    }
+}
+
+impl<T: BitFlag, V> Clone for RulePairs<'_, T, V> {
+    fn clone(&self) -> Self {
+        RulePairs { ..*self }
+    }
+}
+
+/// `flag_pattern!` creates a [`FlagPattern`], with a syntax similar to
+/// [`make_bitflags!`](crate::make_bitflags). List the flags that are required, and prefix
+/// the ones that are forbidden with `!`, separating them with `&`:
+///
+/// ```
+/// # use enumflags2::{bitflags, flag_pattern, FlagPattern};
+/// # #[bitflags]
+/// # #[repr(u8)]
+/// # #[derive(Clone, Copy, Debug)]
+/// # enum Test {
+/// #     A = 1 << 0,
+/// #     B = 1 << 1,
+/// #     C = 1 << 2,
+/// # }
+/// let pattern = flag_pattern!(Test::{A & B & !C});
+/// assert_eq!(pattern, FlagPattern::new(Test::A | Test::B, Test::C));
+///
+/// // Also works in const contexts:
+/// const ANY: FlagPattern<Test> = flag_pattern!(Test::{});
+/// ```
+#[macro_export]
+macro_rules! flag_pattern {
+    ( $enum:ident ::{ $($body:tt)* } ) => {
+        $crate::__flag_pattern!(@munch $enum [] [] $($body)*)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __flag_pattern {
+    (@munch $enum:ident [$($req:ident)*] [$($forb:ident)*] ! $flag:ident $(& $($rest:tt)*)?) => {
+        $crate::__flag_pattern!(@munch $enum [$($req)*] [$($forb)* $flag] $($($rest)*)?)
+    };
+    (@munch $enum:ident [$($req:ident)*] [$($forb:ident)*] $flag:ident $(& $($rest:tt)*)?) => {
+        $crate::__flag_pattern!(@munch $enum [$($req)* $flag] [$($forb)*] $($($rest)*)?)
+    };
+    (@munch $enum:ident [$($req:ident)*] [$($forb:ident)*]) => {
+        $crate::FlagPattern::<$enum> {
+            required: $crate::__flag_pattern!(@flags $enum $($req)*),
+            forbidden: $crate::__flag_pattern!(@flags $enum $($forb)*),
+        }
+    };
+    (@flags $enum:ident) => {
+        $crate::BitFlags::<$enum>::EMPTY
+    };
+    (@flags $enum:ident $($flag:ident)+) => {
+        $crate::make_bitflags!($enum::{$($flag)|+})
+    };
+}
@@ -0,0 +1,390 @@
+use crate::formatting::flag_from_name;
+use crate::{BitFlag, BitFlags, FlagPattern};
+use core::fmt;
+use core::ops::Range;
+use core::str::FromStr;
+
+/// The maximum number of [`FlagPattern`]s a [`Predicate`] may compile to.
+pub const MAX_PREDICATE_TERMS: usize = 1024;
+
+/// The maximum nesting of parentheses and negations in a [`Predicate`].
+pub const MAX_PREDICATE_DEPTH: usize = 64;
+
+/// A boolean expression over the flags of `T`, parsed at runtime.
+///
+/// The syntax is the same as the one accepted by [`flag_expr!`](crate::flag_expr):
+/// flag names can be combined with `!`, `&`, `^` and `|`, in order of
+/// decreasing precedence, and grouped with parentheses. Flags are named by
+/// their canonical name or one of their aliases
+/// (see [Renaming flags](crate#renaming-flags)).
+///
+/// A parsed expression is compiled into a list of [`FlagPattern`]s, so that
+/// evaluating it doesn't need to walk a syntax tree. Since that list can grow
+/// exponentially with the size of the expression, parsing fails for
+/// expressions that would need more than [`MAX_PREDICATE_TERMS`] patterns,
+/// or that nest parentheses and negations more than [`MAX_PREDICATE_DEPTH`]
+/// levels deep.
+///
+/// ```
+/// # use enumflags2::{bitflags, Predicate};
+/// #[bitflags]
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// #[repr(u8)]
+/// enum Role {
+///     Admin = 1 << 0,
+///     Editor = 1 << 1,
+///     ReadOnly = 1 << 2,
+/// }
+///
+/// let rule: Predicate<Role> = "Admin | (Editor & !ReadOnly)".parse().unwrap();
+/// assert!(rule.matches(Role::Admin | Role::ReadOnly));
+/// assert!(rule.matches(Role::Editor));
+/// assert!(!rule.matches(Role::Editor | Role::ReadOnly));
+///
+/// let error = "Admin | Edtor".parse::<Predicate<Role>>().unwrap_err();
+/// assert_eq!(error.span(), 8..13);
+/// assert_eq!(error.to_string(), "unknown flag name at 8..13");
+/// ```
+#[derive(Clone)]
+pub struct Predicate<T: BitFlag> {
+    /// The expression in disjunctive normal form.
+    terms: Vec<FlagPattern<T>>,
+}
+
+impl<T> Predicate<T>
+where
+    T: BitFlag,
+{
+    /// Parse an expression, looking up the flag names in `T`.
+    pub fn parse(input: &str) -> Result<Self, ParseExprError> {
+        let mut parser = Parser {
+            input,
+            position: 0,
+            depth: 0,
+            flag: |name: &str| flag_from_name::<T>(name).map(BitFlags::from_flag),
+        };
+
+        let expr = parser.parse_or()?;
+        parser.skip_whitespace();
+        if parser.position != input.len() {
+            return Err(parser.error_at_token(ParseExprErrorKind::UnexpectedToken));
+        }
+
+        let terms = normal_form(&expr, false).ok_or(ParseExprError {
+            span: 0..input.len(),
+            kind: ParseExprErrorKind::TooComplex,
+        })?;
+        Ok(Predicate { terms })
+    }
+
+    /// Evaluate the expression for the given set of flags.
+    #[inline]
+    pub fn matches<B: Into<BitFlags<T>>>(&self, flags: B) -> bool {
+        let flags = flags.into();
+        self.terms.iter().any(|term| term.matches(flags))
+    }
+
+    /// Return the patterns making up the expression. The expression
+    /// is true if any of them match.
+    #[inline]
+    pub fn patterns(&self) -> &[FlagPattern<T>] {
+        &self.terms
+    }
+}
+
+impl<T> FromStr for Predicate<T>
+where
+    T: BitFlag,
+{
+    type Err = ParseExprError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl<T: BitFlag> fmt::Debug for Predicate<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Predicate")
+            .field("terms", &self.terms)
+            .finish()
+    }
+}
+
+/// The error returned when parsing a [`Predicate`] fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseExprError {
+    span: Range<usize>,
+    kind: ParseExprErrorKind,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum ParseExprErrorKind {
+    UnknownFlag,
+    UnexpectedToken,
+    UnexpectedEnd,
+    TooDeep,
+    TooComplex,
+}
+
+impl ParseExprError {
+    /// Return the range of bytes in the input that caused the error.
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+}
+
+impl fmt::Display for ParseExprError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let what = match self.kind {
+            ParseExprErrorKind::UnknownFlag => "unknown flag name",
+            ParseExprErrorKind::UnexpectedToken => "unexpected token",
+            ParseExprErrorKind::UnexpectedEnd => "unexpected end of expression",
+            ParseExprErrorKind::TooDeep => "expression nested too deeply",
+            ParseExprErrorKind::TooComplex => "expression too complex",
+        };
+
+        write!(fmt, "{} at {}..{}", what, self.span.start, self.span.end)
+    }
+}
+
+#[cfg(enumflags2_core_error)]
+impl core::error::Error for ParseExprError {}
+
+#[cfg(all(feature = "std", not(enumflags2_core_error)))]
+impl std::error::Error for ParseExprError {}
+
+// Chains of the same operator are kept flat, so that the depth of the tree
+// is bounded by the nesting of parentheses and negations.
+enum Expr<N> {
+    Flag(N),
+    Not(Box<Expr<N>>),
+    And(Vec<Expr<N>>),
+    Xor(Vec<Expr<N>>),
+    Or(Vec<Expr<N>>),
+}
+
+struct Parser<'a, F> {
+    input: &'a str,
+    position: usize,
+    /// The number of enclosing parentheses and negations.
+    depth: usize,
+    flag: F,
+}
+
+impl<N, F> Parser<'_, F>
+where
+    F: FnMut(&str) -> Option<N>,
+{
+    fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.position..];
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    /// Consume the given operator, if it comes next.
+    fn eat(&mut self, op: char) -> bool {
+        self.skip_whitespace();
+        if self.input[self.position..].starts_with(op) {
+            self.position += op.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Create an error spanning the next token.
+    fn error_at_token(&self, kind: ParseExprErrorKind) -> ParseExprError {
+        let rest = &self.input[self.position..];
+        let len = match rest.chars().next() {
+            None => {
+                return ParseExprError {
+                    span: self.position..self.position,
+                    kind: ParseExprErrorKind::UnexpectedEnd,
+                }
+            }
+            Some(c) if is_name_char(c) => rest.find(|c| !is_name_char(c)).unwrap_or(rest.len()),
+            Some(c) => c.len_utf8(),
+        };
+
+        ParseExprError {
+            span: self.position..self.position + len,
+            kind,
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expr<N>, ParseExprError> {
+        let mut operands = vec![self.parse_xor()?];
+        while self.eat('|') {
+            operands.push(self.parse_xor()?);
+        }
+        Ok(chain(operands, Expr::Or))
+    }
+
+    fn parse_xor(&mut self) -> Result<Expr<N>, ParseExprError> {
+        let mut operands = vec![self.parse_and()?];
+        while self.eat('^') {
+            operands.push(self.parse_and()?);
+        }
+        Ok(chain(operands, Expr::Xor))
+    }
+
+    fn parse_and(&mut self) -> Result<Expr<N>, ParseExprError> {
+        let mut operands = vec![self.parse_unary()?];
+        while self.eat('&') {
+            operands.push(self.parse_unary()?);
+        }
+        Ok(chain(operands, Expr::And))
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr<N>, ParseExprError> {
+        self.skip_whitespace();
+        let rest = &self.input[self.position..];
+        if rest.starts_with('!') || rest.starts_with('(') {
+            if self.depth == MAX_PREDICATE_DEPTH {
+                return Err(self.error_at_token(ParseExprErrorKind::TooDeep));
+            }
+
+            self.depth += 1;
+            let expr = self.parse_nested();
+            self.depth -= 1;
+            return expr;
+        }
+
+        self.skip_whitespace();
+        let rest = &self.input[self.position..];
+        let len = rest.find(|c| !is_name_char(c)).unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error_at_token(ParseExprErrorKind::UnexpectedToken));
+        }
+
+        let span = self.position..self.position + len;
+        let bits = (self.flag)(&rest[..len]).ok_or(ParseExprError {
+            span,
+            kind: ParseExprErrorKind::UnknownFlag,
+        })?;
+        self.position += len;
+        Ok(Expr::Flag(bits))
+    }
+
+    fn parse_nested(&mut self) -> Result<Expr<N>, ParseExprError> {
+        if self.eat('!') {
+            return Ok(Expr::Not(Box::new(self.parse_unary()?)));
+        }
+
+        self.eat('(');
+        let expr = self.parse_or()?;
+        if !self.eat(')') {
+            return Err(self.error_at_token(ParseExprErrorKind::UnexpectedToken));
+        }
+        Ok(expr)
+    }
+}
+
+/// Build a chain of operands, or return the operand itself if there is only one.
+fn chain<N>(mut operands: Vec<Expr<N>>, op: fn(Vec<Expr<N>>) -> Expr<N>) -> Expr<N> {
+    if operands.len() == 1 {
+        operands.pop().unwrap()
+    } else {
+        op(operands)
+    }
+}
+
+// `-` is not an operator, and appears in kebab-case names.
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
+
+/// Convert an expression (negated if `negate` is set) into disjunctive
+/// normal form: the expression is true if any of the terms match.
+///
+/// Returns `None` if that takes more than [`MAX_PREDICATE_TERMS`] terms.
+fn normal_form<T: BitFlag>(expr: &Expr<BitFlags<T>>, negate: bool) -> Option<Vec<FlagPattern<T>>> {
+    match (expr, negate) {
+        (Expr::Flag(flag), false) => Some(vec![FlagPattern::new(*flag, BitFlags::empty())]),
+        (Expr::Flag(flag), true) => Some(vec![FlagPattern::new(BitFlags::empty(), *flag)]),
+        (Expr::Not(inner), _) => normal_form(inner, !negate),
+        (Expr::And(operands), false) | (Expr::Or(operands), true) => {
+            let mut terms = normal_form(&operands[0], negate)?;
+            for operand in &operands[1..] {
+                terms = conjunction(&terms, &normal_form(operand, negate)?)?;
+            }
+            Some(terms)
+        }
+        (Expr::Or(operands), false) | (Expr::And(operands), true) => {
+            let mut terms = normal_form(&operands[0], negate)?;
+            for operand in &operands[1..] {
+                terms = disjunction(terms, normal_form(operand, negate)?)?;
+            }
+            Some(terms)
+        }
+        (Expr::Xor(operands), _) => {
+            // Keep both the chain so far and its negation: a ^ b is
+            // (a & !b) | (!a & b), and its negation is (a & b) | (!a & !b).
+            let mut terms = normal_form(&operands[0], false)?;
+            let mut negated = normal_form(&operands[0], true)?;
+            for operand in &operands[1..] {
+                let b = normal_form(operand, false)?;
+                let not_b = normal_form(operand, true)?;
+                let next = disjunction(conjunction(&terms, &not_b)?, conjunction(&negated, &b)?)?;
+                negated = disjunction(conjunction(&terms, &b)?, conjunction(&negated, &not_b)?)?;
+                terms = next;
+            }
+            Some(if negate { negated } else { terms })
+        }
+    }
+}
+
+/// The conjunction of two expressions in disjunctive normal form.
+fn conjunction<T: BitFlag>(
+    a: &[FlagPattern<T>],
+    b: &[FlagPattern<T>],
+) -> Option<Vec<FlagPattern<T>>> {
+    if a.len() * b.len() > MAX_PREDICATE_TERMS {
+        return None;
+    }
+
+    let mut terms = Vec::with_capacity(a.len() * b.len());
+    for term_a in a {
+        for term_b in b {
+            let term = FlagPattern::new(
+                term_a.required | term_b.required,
+                term_a.forbidden | term_b.forbidden,
+            );
+            // Skip terms that can never match.
+            if term.is_satisfiable() {
+                terms.push(term);
+            }
+        }
+    }
+    Some(simplify(terms))
+}
+
+/// The disjunction of two expressions in disjunctive normal form.
+fn disjunction<T: BitFlag>(
+    mut a: Vec<FlagPattern<T>>,
+    b: Vec<FlagPattern<T>>,
+) -> Option<Vec<FlagPattern<T>>> {
+    if a.len() + b.len() > MAX_PREDICATE_TERMS {
+        return None;
+    }
+
+    a.extend(b);
+    Some(simplify(a))
+}
+/// Remove the terms that are implied by another term.
+fn simplify<T: BitFlag>(mut terms: Vec<FlagPattern<T>>) -> Vec<FlagPattern<T>> {
+    let mut i = 0;
+    while i < terms.len() {
+        let term = terms[i];
+        let redundant = terms
+            .iter()
+            .enumerate()
+            .any(|(j, &other)| j != i && other.covers(term) && (term != other || j < i));
+        if redundant {
+            terms.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+    terms
+}
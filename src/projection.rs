@@ -0,0 +1,50 @@
+use crate::{BitFlag, BitFlagNum, BitFlags, UnexpectedFlags};
+
+/// Marks `Self` as a subset of the flag enum `Super`: each flag of `Self`
+/// is also a flag of `Super`, with the same name and bit.
+///
+/// Don't implement this trait manually. Use `#[bitflags(subset_of = Super)]`
+/// instead, which checks the above at compile time. See
+/// [the crate documentation](index.html#subsets) for an example.
+///
+/// Rust's coherence rules don't allow `From` and `TryFrom` implementations
+/// between two `BitFlags` types, so the conversions are provided by
+/// [`BitFlags::widen`], [`BitFlags::project`] and [`BitFlags::try_project`].
+pub trait SubsetOf<Super: BitFlag>: BitFlag {}
+
+impl<T> BitFlags<T>
+where
+    T: BitFlag,
+{
+    /// Convert the flags to the corresponding flags of `Super`.
+    #[inline]
+    pub fn widen<Super>(self) -> BitFlags<Super>
+    where
+        T: SubsetOf<Super>,
+        Super: BitFlag,
+    {
+        BitFlags::from_bits_truncate(Super::Numeric::truncate_from_u128(self.bits().to_u128()))
+    }
+
+    /// Convert the flags to the corresponding flags of the subset `Sub`,
+    /// discarding the ones that `Sub` doesn't have.
+    #[inline]
+    pub fn project<Sub>(self) -> BitFlags<Sub>
+    where
+        Sub: SubsetOf<T>,
+    {
+        BitFlags::from_bits_truncate(Sub::Numeric::truncate_from_u128(self.bits().to_u128()))
+    }
+
+    /// Convert the flags to the corresponding flags of the subset `Sub`.
+    /// Fails if some of the flags are not in `Sub`, reporting them in the
+    /// error.
+    #[inline]
+    pub fn try_project<Sub>(self) -> Result<BitFlags<Sub>, UnexpectedFlags<T>>
+    where
+        Sub: SubsetOf<T>,
+    {
+        self.ensure_only(BitFlags::<Sub>::all().widen())?;
+        Ok(self.project())
+    }
+}
@@ -0,0 +1,396 @@
+use crate::{BitFlag, BitFlags, BitFlagNum};
+use core::convert::TryFrom;
+use core::iter::FusedIterator;
+
+impl<T> BitFlags<T>
+where
+    T: BitFlag,
+{
+    /// Iterate over every subset of the `BitFlags`, including the empty set
+    /// and `self`.
+    ///
+    /// The subsets are yielded in increasing order of their numeric value.
+    ///
+    /// ```
+    /// # use enumflags2::{bitflags, make_bitflags, BitFlags};
+    /// # #[bitflags]
+    /// # #[derive(Clone, Copy, PartialEq, Debug)]
+    /// # #[repr(u8)]
+    /// # enum MyFlag {
+    /// #     A = 1 << 0,
+    /// #     B = 1 << 1,
+    /// #     C = 1 << 2,
+    /// # }
+    /// let flags = make_bitflags!(MyFlag::{A | C});
+    ///
+    /// assert_eq!(flags.subsets().collect::<Vec<_>>(), [
+    ///     BitFlags::empty(),
+    ///     MyFlag::A.into(),
+    ///     MyFlag::C.into(),
+    ///     MyFlag::A | MyFlag::C,
+    /// ]);
+    /// ```
+    #[inline]
+    pub fn subsets(self) -> Subsets<T> {
+        Subsets {
+            mask: self,
+            next: Some(BitFlags::empty()),
+        }
+    }
+
+    /// Iterate over every subset of the `BitFlags` that contains exactly
+    /// `k` flags.
+    ///
+    /// The subsets are yielded in increasing order of their numeric value.
+    /// If `k` is larger than [`len`][BitFlags::len], nothing is yielded.
+    ///
+    /// ```
+    /// # use enumflags2::{bitflags, BitFlags};
+    /// # #[bitflags]
+    /// # #[derive(Clone, Copy, PartialEq, Debug)]
+    /// # #[repr(u8)]
+    /// # enum MyFlag {
+    /// #     A = 1 << 0,
+    /// #     B = 1 << 1,
+    /// #     C = 1 << 2,
+    /// # }
+    /// let pairs = BitFlags::<MyFlag>::all().combinations(2);
+    /// assert_eq!(pairs.len(), 3);
+    /// assert_eq!(pairs.collect::<Vec<_>>(), [
+    ///     MyFlag::A | MyFlag::B,
+    ///     MyFlag::A | MyFlag::C,
+    ///     MyFlag::B | MyFlag::C,
+    /// ]);
+    /// ```
+    #[inline]
+    pub fn combinations(self, k: usize) -> Combinations<T> {
+        let n = self.len();
+        let remaining = if k > n { 0 } else { binomial(n as u32, k as u32) };
+
+        Combinations {
+            mask: self,
+            next: low_bits(k as u32),
+            remaining,
+        }
+    }
+
+    /// Iterate over every set that contains `self` and is contained
+    /// in `mask`.
+    ///
+    /// If `self` is not a subset of `mask`, nothing is yielded.
+    ///
+    /// ```
+    /// # use enumflags2::{bitflags, BitFlags};
+    /// # #[bitflags]
+    /// # #[derive(Clone, Copy, PartialEq, Debug)]
+    /// # #[repr(u8)]
+    /// # enum MyFlag {
+    /// #     A = 1 << 0,
+    /// #     B = 1 << 1,
+    /// #     C = 1 << 2,
+    /// # }
+    /// let supersets = BitFlags::from_flag(MyFlag::B)
+    ///     .supersets_within(BitFlags::all());
+    ///
+    /// assert_eq!(supersets.collect::<Vec<_>>(), [
+    ///     MyFlag::B.into(),
+    ///     MyFlag::A | MyFlag::B,
+    ///     MyFlag::B | MyFlag::C,
+    ///     MyFlag::A | MyFlag::B | MyFlag::C,
+    /// ]);
+    /// ```
+    #[inline]
+    pub fn supersets_within<B: Into<BitFlags<T>>>(self, mask: B) -> Supersets<T> {
+        let mask = mask.into();
+        let mut inner = (mask & !self).subsets();
+        if !mask.contains(self) {
+            inner.next = None;
+        }
+
+        Supersets { base: self, inner }
+    }
+
+    /// Iterate over every subset of the `BitFlags` in Gray code order,
+    /// such that consecutive subsets differ by exactly one flag.
+    ///
+    /// ```
+    /// # use enumflags2::{bitflags, make_bitflags, BitFlags};
+    /// # #[bitflags]
+    /// # #[derive(Clone, Copy, PartialEq, Debug)]
+    /// # #[repr(u8)]
+    /// # enum MyFlag {
+    /// #     A = 1 << 0,
+    /// #     B = 1 << 1,
+    /// #     C = 1 << 2,
+    /// # }
+    /// let flags = make_bitflags!(MyFlag::{A | C});
+    ///
+    /// assert_eq!(flags.gray_subsets().collect::<Vec<_>>(), [
+    ///     BitFlags::empty(),
+    ///     MyFlag::A.into(),
+    ///     MyFlag::A | MyFlag::C,
+    ///     MyFlag::C.into(),
+    /// ]);
+    /// ```
+    #[inline]
+    pub fn gray_subsets(self) -> GraySubsets<T> {
+        GraySubsets {
+            mask: self,
+            next: Some(0),
+            last: low_bits(self.len() as u32),
+        }
+    }
+}
+
+/// Iterator over the subsets of a `BitFlags`, created by
+/// [`BitFlags::subsets`].
+///
+/// The number of subsets of a `BitFlags` with more than `usize::BITS - 1`
+/// flags set doesn't fit in a `usize`. Calling
+/// [`len`][ExactSizeIterator::len] on such an iterator will panic.
+#[derive(Clone, Debug)]
+pub struct Subsets<T: BitFlag> {
+    mask: BitFlags<T>,
+    next: Option<BitFlags<T>>,
+}
+
+impl<T> Iterator for Subsets<T>
+where
+    T: BitFlag,
+{
+    type Item = BitFlags<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+        let mask = self.mask.bits();
+        // Subtracting the mask sets all the bits outside of it, which lets
+        // the borrow propagate straight to the next bit inside the mask.
+        let succ = current.bits().wrapping_sub(mask) & mask;
+        self.next = if succ == T::EMPTY {
+            None
+        } else {
+            // SAFETY: `succ` is a subset of `mask`, which only has valid bits set.
+            Some(unsafe { BitFlags::from_bits_unchecked(succ) })
+        };
+        Some(current)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.next {
+            None => (0, Some(0)),
+            Some(current) => {
+                let last = low_bits(self.mask.len() as u32);
+                exact_size_hint(last - extract(current.bits(), self.mask.bits()))
+            }
+        }
+    }
+}
+
+impl<T: BitFlag> ExactSizeIterator for Subsets<T> {}
+
+impl<T: BitFlag> FusedIterator for Subsets<T> {}
+
+/// Iterator over the subsets of a `BitFlags` with a fixed number of flags,
+/// created by [`BitFlags::combinations`].
+///
+/// The number of combinations might not fit in a `usize` for very wide
+/// flag types. Calling [`len`][ExactSizeIterator::len] on such an iterator
+/// will panic.
+#[derive(Clone, Debug)]
+pub struct Combinations<T: BitFlag> {
+    mask: BitFlags<T>,
+    /// The next combination, as a dense index into the bits of `mask`.
+    next: u128,
+    remaining: u128,
+}
+
+impl<T> Iterator for Combinations<T>
+where
+    T: BitFlag,
+{
+    type Item = BitFlags<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let current = self.next;
+        self.remaining -= 1;
+        if self.remaining != 0 {
+            // Gosper's hack: move the lowest block of ones up by one position,
+            // and put all but one of its bits back at the bottom.
+            let lowest = current & current.wrapping_neg();
+            let ripple = current + lowest;
+            self.next = (((ripple ^ current) >> 2) / lowest) | ripple;
+        }
+
+        // SAFETY: `deposit` only sets bits that are set in the mask.
+        Some(unsafe { BitFlags::from_bits_unchecked(deposit(current, self.mask.bits())) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining.checked_sub(1) {
+            Some(after) => exact_size_hint(after),
+            None => (0, Some(0)),
+        }
+    }
+}
+
+impl<T: BitFlag> ExactSizeIterator for Combinations<T> {}
+
+impl<T: BitFlag> FusedIterator for Combinations<T> {}
+
+/// Iterator over the supersets of a `BitFlags` within a mask, created by
+/// [`BitFlags::supersets_within`].
+///
+/// Just like with [`Subsets`], calling [`len`][ExactSizeIterator::len]
+/// will panic if the number of supersets doesn't fit in a `usize`.
+#[derive(Clone, Debug)]
+pub struct Supersets<T: BitFlag> {
+    base: BitFlags<T>,
+    inner: Subsets<T>,
+}
+
+impl<T> Iterator for Supersets<T>
+where
+    T: BitFlag,
+{
+    type Item = BitFlags<T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|extra| self.base | extra)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T: BitFlag> ExactSizeIterator for Supersets<T> {}
+
+impl<T: BitFlag> FusedIterator for Supersets<T> {}
+
+/// Iterator over the subsets of a `BitFlags` in Gray code order, created by
+/// [`BitFlags::gray_subsets`].
+///
+/// Just like with [`Subsets`], calling [`len`][ExactSizeIterator::len]
+/// will panic if the number of subsets doesn't fit in a `usize`.
+#[derive(Clone, Debug)]
+pub struct GraySubsets<T: BitFlag> {
+    mask: BitFlags<T>,
+    /// The index of the next subset in the sequence.
+    next: Option<u128>,
+    last: u128,
+}
+
+impl<T> Iterator for GraySubsets<T>
+where
+    T: BitFlag,
+{
+    type Item = BitFlags<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.next?;
+        self.next = if index == self.last {
+            None
+        } else {
+            Some(index + 1)
+        };
+
+        let gray = index ^ (index >> 1);
+        // SAFETY: `deposit` only sets bits that are set in the mask.
+        Some(unsafe { BitFlags::from_bits_unchecked(deposit(gray, self.mask.bits())) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.next {
+            Some(index) => exact_size_hint(self.last - index),
+            None => (0, Some(0)),
+        }
+    }
+}
+
+impl<T: BitFlag> ExactSizeIterator for GraySubsets<T> {}
+
+impl<T: BitFlag> FusedIterator for GraySubsets<T> {}
+
+/// The size hint for an iterator that has `after + 1` items left.
+///
+/// Takes the count minus one, so that all `2^128` subsets of a `u128`
+/// can be represented.
+fn exact_size_hint(after: u128) -> (usize, Option<usize>) {
+    match after.checked_add(1).map(usize::try_from) {
+        Some(Ok(len)) => (len, Some(len)),
+        _ => (usize::MAX, None),
+    }
+}
+
+/// A number with the lowest `n` bits set.
+fn low_bits(n: u32) -> u128 {
+    if n >= 128 {
+        u128::MAX
+    } else {
+        (1 << n) - 1
+    }
+}
+
+/// `n` choose `k`, for `n <= 128`. The result always fits in a `u128`.
+fn binomial(n: u32, k: u32) -> u128 {
+    fn gcd(mut a: u128, mut b: u128) -> u128 {
+        while b != 0 {
+            let t = a % b;
+            a = b;
+            b = t;
+        }
+        a
+    }
+
+    let k = k.min(n - k);
+    let mut result: u128 = 1;
+    for i in 0..k {
+        // result * (n - i) is always divisible by (i + 1), but could
+        // overflow. Divide out the common factors first.
+        let numerator = u128::from(n - i);
+        let denominator = u128::from(i + 1);
+        let g = gcd(result, denominator);
+        result = (result / g) * (numerator / (denominator / g));
+    }
+    result
+}
+
+/// Scatter the low bits of `dense` onto the bits that are set in `mask`,
+/// starting from the least significant one.
+pub(crate) fn deposit<N: BitFlagNum>(dense: u128, mask: N) -> N {
+    let mut rest = mask;
+    let mut out = N::default();
+    let mut index = 0;
+    while rest != N::default() {
+        let lowest = rest & rest.wrapping_neg();
+        if (dense >> index) & 1 != 0 {
+            out = out | lowest;
+        }
+        rest = rest & (rest - N::ONE);
+        index += 1;
+    }
+    out
+}
+
+/// The inverse of `deposit`: gather the bits of `bits` selected by `mask`
+/// into the low bits of the result.
+pub(crate) fn extract<N: BitFlagNum>(bits: N, mask: N) -> u128 {
+    let mut rest = mask;
+    let mut out = 0;
+    let mut index = 0;
+    while rest != N::default() {
+        let lowest = rest & rest.wrapping_neg();
+        if bits & lowest != N::default() {
+            out |= 1 << index;
+        }
+        rest = rest & (rest - N::ONE);
+        index += 1;
+    }
+    out
+}
@@ -0,0 +1,251 @@
+use crate::{BitFlag, BitFlags};
+use core::fmt::{self, Write};
+
+/// The character shown for flags that are not set, unless another
+/// one is chosen with [`Symbolic::placeholder`].
+const DEFAULT_PLACEHOLDER: char = '-';
+
+/// A fixed-width rendering of a set of flags, in the style of the
+/// permission strings shown by `ls -l`.
+///
+/// Every flag has a column, in declaration order, which shows either
+/// the flag's symbol or, if the flag is not set, a placeholder. Symbols are
+/// chosen with `#[bitflag(symbol = '...')]`, and default to the first
+/// character of the flag's name. Since `-` is the default placeholder, it
+/// can't be chosen as a symbol. A flag whose name starts with `-` needs
+/// another symbol, or another placeholder, for its column to be parsed
+/// back.
+///
+/// Returned by [`BitFlags::symbolic`], and parsed back with
+/// [`BitFlags::from_symbolic`].
+///
+/// ```
+/// # use enumflags2::{bitflags, BitFlags};
+/// #[bitflags]
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// #[repr(u8)]
+/// enum Perm {
+///     #[bitflag(symbol = 'r')]
+///     Read = 1 << 2,
+///     #[bitflag(symbol = 'w')]
+///     Write = 1 << 1,
+///     #[bitflag(symbol = 'x')]
+///     Execute = 1 << 0,
+/// }
+///
+/// let perms = Perm::Read | Perm::Execute;
+/// assert_eq!(perms.symbolic().to_string(), "r-x");
+/// assert_eq!(perms.symbolic().placeholder('.').unwrap().to_string(), "r.x");
+/// assert_eq!(BitFlags::<Perm>::from_symbolic("r-x").unwrap(), perms);
+///
+/// let error = BitFlags::<Perm>::from_symbolic("rwz").unwrap_err();
+/// assert_eq!(error.column(), 2);
+/// assert_eq!(error.to_string(), "expected `x` or `-` at column 2, found `z`");
+///
+/// // A flag would look the same whether it is set or not.
+/// assert!(perms.symbolic().placeholder('w').is_err());
+/// ```
+#[derive(Copy, Clone)]
+pub struct Symbolic<T: BitFlag> {
+    flags: BitFlags<T>,
+    placeholder: char,
+}
+
+impl<T: BitFlag> Symbolic<T> {
+    /// Show flags that are not set with `placeholder`, instead of `-`.
+    ///
+    /// Fails if `placeholder` is the symbol of one of the flags.
+    pub fn placeholder(self, placeholder: char) -> Result<Self, PlaceholderError> {
+        check_placeholder::<T>(placeholder)?;
+        Ok(Symbolic {
+            placeholder,
+            ..self
+        })
+    }
+}
+
+impl<T: BitFlag> fmt::Display for Symbolic<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for &(bits, symbol) in T::SYMBOLS {
+            if self.flags.bits() & bits == bits {
+                fmt.write_char(symbol)?;
+            } else {
+                fmt.write_char(self.placeholder)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: BitFlag> fmt::Debug for Symbolic<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "\"{}\"", self)
+    }
+}
+
+impl<T> BitFlags<T>
+where
+    T: BitFlag,
+{
+    /// Render the flags as a fixed-width string, with one column
+    /// per flag. See [`Symbolic`] for details.
+    pub fn symbolic(self) -> Symbolic<T> {
+        Symbolic {
+            flags: self,
+            placeholder: DEFAULT_PLACEHOLDER,
+        }
+    }
+
+    /// Parse the output of [`symbolic`][BitFlags::symbolic], with `-`
+    /// as the placeholder.
+    pub fn from_symbolic(s: &str) -> Result<Self, ParseSymbolicError> {
+        Self::from_symbolic_with_placeholder(s, DEFAULT_PLACEHOLDER)
+    }
+
+    /// Parse the output of [`symbolic`][BitFlags::symbolic], with the
+    /// given placeholder.
+    ///
+    /// Fails if `placeholder` is the symbol of one of the flags.
+    pub fn from_symbolic_with_placeholder(
+        s: &str,
+        placeholder: char,
+    ) -> Result<Self, ParseSymbolicError> {
+        if let Err(PlaceholderError { column, .. }) = check_placeholder::<T>(placeholder) {
+            return Err(ParseSymbolicError {
+                column,
+                expected: None,
+                placeholder,
+                found: None,
+                ambiguous: true,
+            });
+        }
+
+        let mut flags = BitFlags::empty();
+        let mut chars = s.chars();
+        for (column, &(bits, symbol)) in T::SYMBOLS.iter().enumerate() {
+            let error = |found| ParseSymbolicError {
+                column,
+                expected: Some(symbol),
+                placeholder,
+                found,
+                ambiguous: false,
+            };
+
+            match chars.next() {
+                Some(c) if c == symbol => flags |= BitFlags::from_bits_truncate(bits),
+                Some(c) if c == placeholder => {}
+                found => return Err(error(found)),
+            }
+        }
+
+        match chars.next() {
+            None => Ok(flags),
+            found => Err(ParseSymbolicError {
+                column: T::SYMBOLS.len(),
+                expected: None,
+                placeholder,
+                found,
+                ambiguous: false,
+            }),
+        }
+    }
+}
+
+/// The error returned when parsing the output of
+/// [`symbolic`][BitFlags::symbolic] fails.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ParseSymbolicError {
+    column: usize,
+    expected: Option<char>,
+    placeholder: char,
+    found: Option<char>,
+    /// Set if the placeholder is the symbol of the flag at `column`.
+    ambiguous: bool,
+}
+
+impl ParseSymbolicError {
+    /// Return the column, counted in characters from zero, at which
+    /// the error occurred.
+    pub fn column(self) -> usize {
+        self.column
+    }
+}
+
+impl fmt::Display for ParseSymbolicError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ambiguous {
+            return PlaceholderError {
+                placeholder: self.placeholder,
+                column: self.column,
+            }
+            .fmt(fmt);
+        }
+
+        match (self.expected, self.found) {
+            (Some(symbol), Some(found)) => write!(
+                fmt,
+                "expected `{}` or `{}` at column {}, found `{}`",
+                symbol, self.placeholder, self.column, found
+            ),
+            (Some(symbol), None) => write!(
+                fmt,
+                "expected `{}` or `{}` at column {}, found end of input",
+                symbol, self.placeholder, self.column
+            ),
+            (None, _) => write!(
+                fmt,
+                "expected end of input at column {}",
+                self.column
+            ),
+        }
+    }
+}
+
+#[cfg(enumflags2_core_error)]
+impl core::error::Error for ParseSymbolicError {}
+
+#[cfg(all(feature = "std", not(enumflags2_core_error)))]
+impl std::error::Error for ParseSymbolicError {}
+
+/// Check that `placeholder` can't be mistaken for the symbol of a flag.
+fn check_placeholder<T: BitFlag>(placeholder: char) -> Result<(), PlaceholderError> {
+    match T::SYMBOLS.iter().position(|&(_, symbol)| symbol == placeholder) {
+        Some(column) => Err(PlaceholderError {
+            placeholder,
+            column,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// The error returned when the placeholder chosen for
+/// [`symbolic`][BitFlags::symbolic] is also the symbol of a flag, which
+/// would then look the same whether it is set or not.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PlaceholderError {
+    placeholder: char,
+    column: usize,
+}
+
+impl PlaceholderError {
+    /// Return the column of the flag whose symbol is the placeholder.
+    pub fn column(self) -> usize {
+        self.column
+    }
+}
+
+impl fmt::Display for PlaceholderError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            fmt,
+            "placeholder `{}` is also the symbol at column {}",
+            self.placeholder, self.column
+        )
+    }
+}
+
+#[cfg(enumflags2_core_error)]
+impl core::error::Error for PlaceholderError {}
+
+#[cfg(all(feature = "std", not(enumflags2_core_error)))]
+impl std::error::Error for PlaceholderError {}
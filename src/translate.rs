@@ -0,0 +1,156 @@
+use crate::{BitFlag, BitFlags, UnexpectedFlags};
+
+/// A mapping from the flags of `Self` to the flags of `Target`, which
+/// may have a different layout.
+///
+/// Don't implement this trait manually. Use [`translate_flags!`](crate::translate_flags) instead,
+/// which checks that the mapping is complete at compile time.
+///
+/// Rust's coherence rules don't allow `From` and `TryFrom` implementations
+/// between two `BitFlags` types, so the conversions are provided by
+/// [`BitFlags::translate`] and [`BitFlags::try_translate`].
+pub trait TranslateFlags<Target: BitFlag>: BitFlag {
+    /// The flags that have no counterpart in `Target`.
+    const LOSSY: Self::Numeric;
+
+    /// Translate the bits of the mapped flags, ignoring all the others.
+    fn translate_bits(bits: Self::Numeric) -> Target::Numeric;
+}
+
+impl<T> BitFlags<T>
+where
+    T: BitFlag,
+{
+    /// Convert the flags to the corresponding flags of `U`, according to
+    /// a mapping declared with [`translate_flags!`](crate::translate_flags). Flags that the mapping
+    /// marks as lossy are dropped.
+    #[inline]
+    pub fn translate<U>(self) -> BitFlags<U>
+    where
+        T: TranslateFlags<U>,
+        U: BitFlag,
+    {
+        BitFlags::from_bits_truncate(T::translate_bits(self.bits()))
+    }
+
+    /// Convert the flags to the corresponding flags of `U`. Fails if some
+    /// of the flags have no counterpart in `U`, reporting them in the error.
+    #[inline]
+    pub fn try_translate<U>(self) -> Result<BitFlags<U>, UnexpectedFlags<T>>
+    where
+        T: TranslateFlags<U>,
+        U: BitFlag,
+    {
+        self.ensure_only(!BitFlags::from_bits_truncate(T::LOSSY))?;
+        Ok(self.translate())
+    }
+}
+
+/// `translate_flags!` declares a mapping between the flags of two
+/// `#[bitflags]` enums with different layouts, and implements
+/// [`TranslateFlags`] for it.
+///
+/// Each entry of the mapping is either `Source => Target`, or a single
+/// name for flags that are called the same in both enums. Every flag of the
+/// source enum must be mapped, or explicitly marked as lossy with
+/// `Source => _`. Otherwise, the mapping fails to compile. The translation
+/// itself only moves bits around, without any lookups.
+///
+/// ```
+/// # use enumflags2::{bitflags, translate_flags, BitFlags};
+/// #[bitflags]
+/// #[repr(u8)]
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// enum Perm {
+///     Read = 1 << 0,
+///     Write = 1 << 1,
+///     Admin = 1 << 2,
+/// }
+///
+/// #[bitflags]
+/// #[repr(u32)]
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// enum Wire {
+///     Write = 1 << 3,
+///     ReadBit = 1 << 5,
+///     Sticky = 1 << 9,
+/// }
+///
+/// translate_flags! {
+///     Perm <=> Wire {
+///         Read => ReadBit,
+///         Write,
+///         Admin => _,
+///         _ => Sticky,
+///     }
+/// }
+///
+/// let perm = Perm::Read | Perm::Write;
+/// assert_eq!(perm.translate::<Wire>(), Wire::ReadBit | Wire::Write);
+/// assert_eq!(perm.translate::<Wire>().translate::<Perm>(), perm);
+///
+/// // Lossy flags are dropped by `translate`, and rejected by `try_translate`.
+/// let wire = Wire::ReadBit | Wire::Sticky;
+/// assert_eq!(wire.translate::<Perm>(), Perm::Read);
+/// assert_eq!(wire.try_translate::<Perm>().unwrap_err().unexpected(), Wire::Sticky);
+/// ```
+///
+/// With `=>` instead of `<=>`, the mapping only goes from the first enum
+/// to the second one, so the flags of the second enum don't all need to be
+/// listed:
+///
+/// ```
+/// # use enumflags2::{bitflags, translate_flags};
+/// # #[bitflags]
+/// # #[repr(u8)]
+/// # #[derive(Clone, Copy, Debug, PartialEq)]
+/// # enum Perm {
+/// #     Read = 1 << 0,
+/// #     Write = 1 << 1,
+/// # }
+/// # #[bitflags]
+/// # #[repr(u32)]
+/// # #[derive(Clone, Copy, Debug, PartialEq)]
+/// # enum Wire {
+/// #     Write = 1 << 3,
+/// #     ReadBit = 1 << 5,
+/// #     Sticky = 1 << 9,
+/// # }
+/// translate_flags! {
+///     Perm => Wire {
+///         Read => ReadBit,
+///         Write,
+///     }
+/// }
+/// ```
+///
+/// Forgetting a flag is an error:
+///
+/// ```compile_fail
+/// # use enumflags2::{bitflags, translate_flags};
+/// # #[bitflags]
+/// # #[repr(u8)]
+/// # #[derive(Clone, Copy, Debug, PartialEq)]
+/// # enum Perm {
+/// #     Read = 1 << 0,
+/// #     Write = 1 << 1,
+/// # }
+/// # #[bitflags]
+/// # #[repr(u32)]
+/// # #[derive(Clone, Copy, Debug, PartialEq)]
+/// # enum Wire {
+/// #     Write = 1 << 3,
+/// #     ReadBit = 1 << 5,
+/// # }
+/// translate_flags! {
+///     Perm => Wire {
+///         Read => ReadBit,
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! translate_flags {
+    ( $($input:tt)* ) => {
+        $crate::_internal::translate_flags_internal!($($input)*);
+    };
+}
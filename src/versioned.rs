@@ -0,0 +1,208 @@
+use crate::{BitFlag, BitFlags, FromBitsError};
+
+/// Return the value of `flag` in the given version of the layout.
+fn value_in_version<T: BitFlag>(flag: T::Numeric, version: u32) -> T::Numeric {
+    T::HISTORY
+        .iter()
+        .filter(|&&(current, _, until)| current == flag && version < until)
+        .min_by_key(|&&(_, _, until)| until)
+        .map_or(flag, |&(_, previous, _)| previous)
+}
+
+/// Interpret `bits` with the given version of the layout, returning the
+/// flags and the bits that didn't correspond to any flag.
+fn remap<T: BitFlag>(bits: T::Numeric, version: u32) -> (BitFlags<T>, T::Numeric) {
+    let mut flags = BitFlags::empty();
+    let mut known = T::EMPTY;
+    for flag in BitFlags::<T>::all().iter() {
+        let value = value_in_version::<T>(flag.bits(), version);
+        known = known | value;
+        if bits & value != T::EMPTY {
+            flags |= flag;
+        }
+    }
+
+    (flags, bits & !known)
+}
+
+/// Return the value of `flag` in the given version, like `value_in_version`,
+/// with the values widened to `u128`.
+const fn widened_value_in_version(flag: u128, history: &[(u128, u128, u32)], version: u32) -> u128 {
+    let mut value = flag;
+    let mut earliest = u32::MAX;
+    let mut i = 0;
+    while i < history.len() {
+        let (current, previous, until) = history[i];
+        if current == flag && version < until && until < earliest {
+            value = previous;
+            earliest = until;
+        }
+        i += 1;
+    }
+    value
+}
+
+/// Used by `#[bitflags]` to check that no two flags had the same value in
+/// any older version of the layout, which would make `from_bits_versioned`
+/// set both.
+pub const fn history_is_disjoint(all_bits: u128, history: &[(u128, u128, u32)]) -> bool {
+    let mut i = 0;
+    while i < history.len() {
+        let until = history[i].2;
+        if until > 0 {
+            // The layout in effect just before the change.
+            let mut seen = 0;
+            let mut rest = all_bits;
+            while rest != 0 {
+                let flag = rest & rest.wrapping_neg();
+                rest &= rest - 1;
+                let value = widened_value_in_version(flag, history, until - 1);
+                if seen & value != 0 {
+                    return false;
+                }
+                seen |= value;
+            }
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Find the most recent older layout in which `bits` is valid, for
+/// the context of a [`FromBitsError`].
+pub(crate) fn legacy_layout<T: BitFlag>(bits: T::Numeric) -> Option<u32> {
+    T::HISTORY
+        .iter()
+        .map(|&(_, _, until)| until)
+        .filter(|&until| until > 0 && remap::<T>(bits, until - 1).1 == T::EMPTY)
+        .max()
+}
+
+impl<T> BitFlags<T>
+where
+    T: BitFlag,
+{
+    /// Create a `BitFlags` from a value written by the given version of
+    /// the program, taking into account the flags that
+    /// [changed their value](index.html#versioned-values) since then.
+    ///
+    /// Returns an error if the value has bits that didn't correspond to any
+    /// flag in that version. The invalid bits are reported as they were in
+    /// the input.
+    pub fn from_bits_versioned(bits: T::Numeric, version: u32) -> Result<Self, FromBitsError<T>> {
+        if T::HISTORY.iter().all(|&(_, _, until)| until <= version) {
+            return Self::from_bits(bits);
+        }
+
+        let (flags, invalid) = remap::<T>(bits, version);
+        if invalid == T::EMPTY {
+            Ok(flags)
+        } else {
+            Err(FromBitsError {
+                flags,
+                invalid,
+                legacy: None,
+            })
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+pub use self::impl_serde::{deserialize_from_version, FromVersion};
+
+#[cfg(feature = "serde")]
+mod impl_serde {
+    use crate::{BitFlag, BitFlags};
+    use core::marker::PhantomData;
+    use serde::de::{DeserializeSeed, Error, Unexpected};
+    use serde::{Deserialize, Deserializer};
+
+    /// Deserializes a `BitFlags<T>` written by the given version of the
+    /// program, with [`BitFlags::from_bits_versioned`].
+    ///
+    /// This is a [`DeserializeSeed`], for when the version is only known
+    /// at runtime, usually because it is stored next to the flags. For
+    /// a fixed version, see [`deserialize_from_version`].
+    ///
+    /// ```
+    /// # use enumflags2::{bitflags, FromVersion};
+    /// use serde::de::{DeserializeSeed, IntoDeserializer};
+    /// # type Error = serde::de::value::Error;
+    ///
+    /// #[bitflags]
+    /// #[derive(Clone, Copy, Debug, PartialEq)]
+    /// #[repr(u8)]
+    /// enum Perm {
+    ///     Read = 1 << 0,
+    ///     #[bitflag(previously = 1 << 2, until = 2)]
+    ///     Write = 1 << 1,
+    /// }
+    ///
+    /// let seed = FromVersion::<Perm>::new(1);
+    /// let flags = seed.deserialize(0b101u8.into_deserializer()).map_err(|e: Error| e);
+    /// assert_eq!(flags.unwrap(), Perm::Read | Perm::Write);
+    /// ```
+    pub struct FromVersion<T> {
+        version: u32,
+        marker: PhantomData<T>,
+    }
+
+    impl<T> FromVersion<T> {
+        /// Deserialize values written by the given version.
+        pub fn new(version: u32) -> Self {
+            FromVersion {
+                version,
+                marker: PhantomData,
+            }
+        }
+    }
+
+    impl<'a, T> DeserializeSeed<'a> for FromVersion<T>
+    where
+        T: BitFlag,
+        T::Numeric: Deserialize<'a> + Into<u64>,
+    {
+        type Value = BitFlags<T>;
+
+        fn deserialize<D: Deserializer<'a>>(self, d: D) -> Result<Self::Value, D::Error> {
+            let val = T::Numeric::deserialize(d)?;
+            BitFlags::from_bits_versioned(val, self.version).map_err(|_| {
+                D::Error::invalid_value(
+                    Unexpected::Unsigned(val.into()),
+                    &"valid bit representation",
+                )
+            })
+        }
+    }
+
+    /// Deserializes a `BitFlags<T>` written by version `VERSION` of the
+    /// program, for use with
+    /// `#[serde(deserialize_with = "enumflags2::deserialize_from_version::<1, _, _>")]`.
+    ///
+    /// ```
+    /// # use enumflags2::{bitflags, BitFlags};
+    /// use serde::de::IntoDeserializer;
+    /// # type Error = serde::de::value::Error;
+    /// # #[bitflags]
+    /// # #[derive(Clone, Copy, Debug, PartialEq)]
+    /// # #[repr(u8)]
+    /// # enum Perm {
+    /// #     Read = 1 << 0,
+    /// #     #[bitflag(previously = 1 << 2, until = 2)]
+    /// #     Write = 1 << 1,
+    /// # }
+    /// let d = 0b100u8.into_deserializer();
+    /// let perms = enumflags2::deserialize_from_version::<1, Perm, _>(d).map_err(|e: Error| e);
+    /// assert_eq!(perms.unwrap(), Perm::Write);
+    /// ```
+    pub fn deserialize_from_version<'a, const VERSION: u32, T, D>(
+        d: D,
+    ) -> Result<BitFlags<T>, D::Error>
+    where
+        T: BitFlag,
+        T::Numeric: Deserialize<'a> + Into<u64>,
+        D: Deserializer<'a>,
+    {
+        FromVersion::new(VERSION).deserialize(d)
+    }
+}
@@ -37,6 +37,73 @@ enum AssociatedTypeNameConflict {
     Numeric = 1 << 1,
 }
 
+#[bitflags(subset_of = Test)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[repr(u8)]
+enum SubTest {
+    B = 1 << 1,
+    D = 1 << 3,
+}
+
+#[bitflags]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[repr(u16)]
+enum Shuffled {
+    A = 1 << 9,
+    B = 1 << 0,
+    D = 1 << 4,
+    E = 1 << 15,
+}
+
+#[bitflags(reserved(Spare = 1 << 7), accessors = RenumberedFlags)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[repr(u8)]
+enum Renumbered {
+    A = 1 << 0,
+    #[bitflag(previously = 1 << 2, until = 3)]
+    #[bitflag(previously = 1 << 4, until = 1)]
+    B = 1 << 1,
+    #[bitflag(previously = 1 << 1, until = 3)]
+    C = 1 << 2,
+}
+
+#[bitflags(rename_all = "kebab-case", display, from_str, accessors, bools)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[repr(u8)]
+enum Renamed {
+    /// Only allows reading.
+    ReadOnly = 1 << 0,
+    /// Allows reading and writing.
+    ///
+    /// Implies `ReadOnly`.
+    #[bitflag(rename = "rw", alias = "read-write", alias = "ReadWrite")]
+    #[bitflag(symbol = 'w', label = "Read and write")]
+    ReadWrite = 1 << 1,
+    #[bitflag(alias = "exec", symbol = 'x')]
+    Execute = 1 << 2,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, enumflags2::IntoBitFlags)]
+#[bitflag(flags = Test)]
+#[allow(clippy::struct_excessive_bools)]
+struct TestBools {
+    a: bool,
+    #[bitflag(flag = B)]
+    second: bool,
+    c: bool,
+    d: bool,
+}
+
+enumflags2::translate_flags! {
+    Test <=> Shuffled {
+        A,
+        B,
+        C => _,
+        D,
+        _ => E,
+    }
+}
+
 #[test]
 fn test_ctors() {
     use enumflags2::BitFlags;
@@ -202,3 +269,485 @@ fn inferred_values() {
     assert_eq!(OnlyInferred::Infer4 as u8, 4);
     assert_eq!(OnlyInferred::Infer8 as u8, 8);
 }
+
+#[test]
+fn subsets() {
+    use enumflags2::BitFlags;
+
+    #[bitflags]
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    #[repr(u128)]
+    enum Wide {
+        Low = 1 << 0,
+        Mid = 1 << 64,
+        High = 1 << 127,
+    }
+
+    let all = BitFlags::<Test>::all();
+    assert_eq!(all.subsets().len(), 16);
+    assert!(all.subsets().zip(0..).all(|(s, i)| s.bits() == i));
+    assert_eq!(BitFlags::<Test>::empty().subsets().len(), 1);
+    assert_eq!((Test::B | Test::D).subsets().last(), Some(Test::B | Test::D));
+
+    for k in 0..=5 {
+        let expected = all.subsets().filter(|s| s.len() == k).count();
+        let mut it = all.combinations(k);
+        assert_eq!(it.len(), expected);
+        assert!(it.by_ref().all(|s| s.len() == k));
+        assert_eq!(it.len(), 0);
+    }
+
+    let mut supersets = BitFlags::from_flag(Test::A).supersets_within(Test::A | Test::C);
+    assert_eq!(supersets.len(), 2);
+    assert!(supersets.all(|s| s.contains(Test::A)));
+    assert_eq!(BitFlags::from_flag(Test::A).supersets_within(Test::B).count(), 0);
+
+    let mut gray = all.gray_subsets();
+    assert_eq!(gray.len(), 16);
+    let mut prev = gray.next().unwrap();
+    for s in gray {
+        assert_eq!((s ^ prev).len(), 1);
+        prev = s;
+    }
+
+    let wide = BitFlags::<Wide>::all();
+    assert_eq!(wide.subsets().len(), 8);
+    assert_eq!(wide.combinations(2).last(), Some(Wide::Mid | Wide::High));
+    assert_eq!(wide.gray_subsets().last(), Some(Wide::High.into()));
+    assert!(wide.subsets().zip(wide.gray_subsets()).all(|(a, b)| wide.contains(a | b)));
+}
+
+#[test]
+fn combinators() {
+    use enumflags2::BitFlags;
+
+    let mut flags = Test::A | Test::B | Test::C;
+    flags.retain(|flag| flag != Test::B);
+    assert_eq!(flags, Test::A | Test::C);
+    assert_eq!(flags.filter(|flag| flag == Test::C), Test::C);
+    assert_eq!(flags.partition(|flag| flag == Test::A), (Test::A.into(), Test::C.into()));
+
+    let mut drained = flags.drain();
+    assert!(flags.is_empty());
+    assert_eq!(drained.next(), Some(Test::A));
+    assert_eq!(drained.next(), Some(Test::C));
+    assert_eq!(drained.next(), None);
+
+    let mapped: BitFlags<Test> = (Test::A | Test::B).map_flags(|flag| match flag {
+        Test::A => Test::C,
+        _ => Test::D,
+    });
+    assert_eq!(mapped, Test::C | Test::D);
+
+    let refs = [Test::A, Test::D];
+    let mut collected: BitFlags<Test> = refs.iter().collect();
+    assert_eq!(collected, Test::A | Test::D);
+    collected.extend(&[Test::B, Test::D]);
+    assert_eq!(collected, Test::A | Test::B | Test::D);
+    assert_eq!(refs.iter().sum::<BitFlags<Test>>(), Test::A | Test::D);
+
+    let mut count = 0;
+    for flag in &collected {
+        assert!(collected.contains(flag));
+        count += 1;
+    }
+    assert_eq!(count, 3);
+}
+
+#[test]
+fn patches() {
+    use enumflags2::{BitFlags, FlagsPatch};
+
+    let old = Test::A | Test::B;
+    let new = Test::B | Test::C;
+    let patch = old.diff(new);
+    assert_eq!(patch, FlagsPatch::new(Test::C, Test::A));
+    assert_eq!(patch.apply(old), new);
+    assert_eq!(patch.apply(Test::D), Test::C | Test::D);
+    assert_eq!(patch.invert().apply(new), old);
+    assert!(old.diff(old).is_empty());
+    assert_eq!(FlagsPatch::<Test>::default(), FlagsPatch::empty());
+
+    let next = FlagsPatch::new(Test::A, Test::C | Test::D);
+    let both = patch.then(next);
+    for flags in BitFlags::<Test>::all().subsets() {
+        assert_eq!(both.apply(flags), next.apply(patch.apply(flags)));
+    }
+
+    let parsed: FlagsPatch<Test> = " +C\t-A  +D ".parse().unwrap();
+    assert_eq!(parsed, FlagsPatch::new(Test::C | Test::D, Test::A));
+    assert_eq!("".parse::<FlagsPatch<Test>>(), Ok(FlagsPatch::empty()));
+    assert_eq!("<empty>".parse::<FlagsPatch<Test>>(), Ok(FlagsPatch::empty()));
+    assert_eq!("+A B".parse::<FlagsPatch<Test>>().unwrap_err().position(), 3);
+    assert_eq!("+A -E".parse::<FlagsPatch<Test>>().unwrap_err().position(), 4);
+}
+
+#[test]
+fn layers() {
+    use enumflags2::{BitFlags, FlagLayers, FlagsPatch, Provenance};
+
+    let layers = [
+        FlagsPatch::new(Default6::A, Default6::B),
+        FlagsPatch::new(Default6::D, BitFlags::empty()),
+        FlagsPatch::new(BitFlags::empty(), Default6::A),
+    ];
+
+    let resolved = FlagLayers::from_default(&layers);
+    assert_eq!(resolved.base(), Default6::B | Default6::C);
+    assert_eq!(resolved.resolve(), Default6::C | Default6::D);
+    assert_eq!(resolved.decided_by(Default6::A), Provenance::Layer(2));
+    assert_eq!(resolved.decided_by(Default6::B), Provenance::Layer(0));
+    assert_eq!(resolved.decided_by(Default6::C), Provenance::Base);
+    assert_eq!(resolved.decided_by(Default6::D), Provenance::Layer(1));
+
+    let mut decisions = resolved.decisions();
+    assert_eq!(decisions.len(), 4);
+    assert!(decisions.all(|(flag, enabled, _)| resolved.resolve().contains(flag) == enabled));
+
+    assert_eq!(FlagsPatch::new(Test::A, Test::B).state(Test::A), Some(true));
+    assert_eq!(FlagsPatch::new(Test::A, Test::B).state(Test::B), Some(false));
+    assert_eq!(FlagsPatch::new(Test::A, Test::B).state(Test::C), None);
+    assert_eq!(FlagLayers::new(Test::A, &[]).resolve(), Test::A);
+}
+
+#[test]
+fn flag_expressions() {
+    use enumflags2::BitFlags;
+
+    let rule = enumflags2::flag_expr!(Test, A | (B & !C) ^ D);
+    for flags in BitFlags::<Test>::all().subsets() {
+        let expected = flags.contains(Test::A)
+            || ((flags.contains(Test::B) && !flags.contains(Test::C)) != flags.contains(Test::D));
+        assert_eq!(rule(flags), expected);
+    }
+
+    let never: fn(BitFlags<Test>) -> bool = enumflags2::flag_expr!(Test, A & !A);
+    assert!(BitFlags::<Test>::all().subsets().all(|flags| !never(flags)));
+}
+
+#[test]
+fn rule_tables() {
+    use enumflags2::{flag_pattern, BitFlags, FlagPattern, RuleTable};
+
+    const LIST: &[(FlagPattern<Test>, u8)] = &[
+        (flag_pattern!(Test::{A & !B}), 0),
+        (flag_pattern!(Test::{B & !B}), 1),
+        (flag_pattern!(Test::{C}), 2),
+        (flag_pattern!(Test::{A & C & !B & !D}), 3),
+        (flag_pattern!(Test::{}), 4),
+    ];
+    const RULES: RuleTable<Test, u8> = RuleTable::new(LIST);
+
+    assert_eq!(RULES.classify(Test::A | Test::C), Some(&0));
+    assert_eq!(RULES.classify(Test::B | Test::C), Some(&2));
+    assert_eq!(RULES.classify(Test::B), Some(&4));
+    assert_eq!(RULES.position(Test::D), Some(4));
+
+    assert!(!RULES.rules()[1].0.is_satisfiable());
+    assert!(RULES.shadowed().eq([(1, 0), (3, 0), (3, 2)].iter().copied()));
+    assert!(RULES
+        .overlaps()
+        .eq([(2, 0), (3, 0), (3, 2), (4, 0), (4, 2), (4, 3)].iter().copied()));
+
+    let pattern = FlagPattern::new(Test::A, Test::B | Test::C);
+    for flags in BitFlags::<Test>::all().subsets() {
+        let expected = flags.contains(Test::A) && !flags.intersects(Test::B | Test::C);
+        assert_eq!(pattern.matches(flags), expected);
+        assert!(FlagPattern::any().matches(flags));
+    }
+    assert!(FlagPattern::any().covers(pattern));
+    assert!(!pattern.covers(FlagPattern::any()));
+}
+
+#[test]
+fn bitflags_match() {
+    use enumflags2::BitFlags;
+
+    fn classify(flags: BitFlags<Test>) -> u8 {
+        enumflags2::bitflags_match!(flags, {
+            exact Test::{} => 0,
+            contains Test::{A | B} => 1,
+            any Test::{C | D} => { 2 }
+            exact Test::A => 3,
+            exact Test::B => 4,
+        })
+    }
+
+    for flags in BitFlags::<Test>::all().subsets() {
+        let expected = if flags.is_empty() {
+            0
+        } else if flags.contains(Test::A | Test::B) {
+            1
+        } else if flags.intersects(Test::C | Test::D) {
+            2
+        } else if flags == Test::A {
+            3
+        } else {
+            4
+        };
+        assert_eq!(classify(flags), expected);
+    }
+
+    let matched = enumflags2::bitflags_match!(Test::D, {
+        contains Test::{A} => false,
+        any Test::{B | C} => false,
+        _ => true,
+    });
+    assert!(matched);
+}
+
+#[test]
+fn flag_functions() {
+    use enumflags2::{BitFlags, FlagFunction, FlagPattern};
+
+    let a = FlagFunction::flag(Test::A);
+    let pattern = FlagPattern::new(Test::B, Test::C);
+    let f = a.or(pattern.into()) & !FlagFunction::flag(Test::D);
+    for flags in BitFlags::<Test>::all().subsets() {
+        let expected = (flags.contains(Test::A) || pattern.matches(flags))
+            && !flags.contains(Test::D);
+        assert_eq!(f.matches(flags), expected);
+    }
+
+    assert_eq!(f.count(), f.satisfying().len());
+    assert!(f.satisfying().all(|flags| f.matches(flags)));
+    assert_eq!(FlagFunction::<Test>::constant(true).count(), 16);
+    assert_eq!(FlagFunction::<Test>::constant(false).satisfying().next(), None);
+    assert_eq!(!a | a, FlagFunction::constant(true));
+    assert_eq!(FlagFunction::from_fn(|flags: BitFlags<Test>| flags.len() == 2).count(), 6);
+}
+
+#[test]
+fn require_and_ensure_only() {
+    use enumflags2::{BitFlags, FromBitsError};
+
+    let flags = Test::A | Test::C;
+    assert!(flags.require(Test::A).is_ok());
+    assert!(flags.require(BitFlags::empty()).is_ok());
+    assert_eq!(flags.require(Test::A | Test::B | Test::D).unwrap_err().missing(), Test::B | Test::D);
+
+    assert!(flags.ensure_only(flags).is_ok());
+    assert!(BitFlags::<Test>::empty().ensure_only(BitFlags::empty()).is_ok());
+    let error = flags.ensure_only(Test::C | Test::D).unwrap_err();
+    assert_eq!(error.unexpected(), Test::A);
+    assert_eq!(error.truncate(), Test::C);
+
+    let error = FromBitsError::from(error);
+    assert_eq!(error.truncate(), Test::C);
+    assert_eq!(error.invalid_bits(), Test::A as u8);
+}
+
+#[test]
+fn capabilities() {
+    use enumflags2::{BitFlags, Capabilities};
+
+    let mut caps = Capabilities::grant(Test::A | Test::B | Test::C);
+    caps.remove(Test::B);
+    assert_eq!(caps.flags(), Test::A | Test::C);
+    assert_eq!(caps.restrict_to(Test::C | Test::D).flags(), Test::C);
+
+    caps &= Capabilities::grant(Test::A | Test::D);
+    assert_eq!(BitFlags::from(caps), Test::A);
+    assert_eq!(caps.len(), 1);
+    assert!(caps.contains(Test::A) && !caps.intersects(Test::D));
+    assert_eq!(caps.require(Test::A | Test::D).unwrap_err().missing(), Test::D);
+    assert!(caps.into_iter().eq(caps.iter()));
+    assert!(Capabilities::<Test>::none().is_empty());
+}
+
+#[test]
+fn non_empty() {
+    use enumflags2::{BitFlags, NonEmptyBitFlags};
+
+    let flags = NonEmptyBitFlags::new(Test::B | Test::D).unwrap();
+    assert_eq!(flags, Test::B | Test::D);
+    assert_eq!(flags.len(), 2);
+    assert_eq!(flags.first(), Test::B);
+    assert!(flags.contains(Test::D) && !flags.intersects(Test::A));
+    assert!(flags.split_first().1.eq(flags.iter().skip(1)));
+    assert!(NonEmptyBitFlags::new(BitFlags::<Test>::empty()).is_none());
+    assert_eq!(NonEmptyBitFlags::<Test>::all().flags(), BitFlags::all());
+    assert_eq!(NonEmptyBitFlags::<Test1>::new(Test1::E).unwrap().bits(), 1 << 34);
+
+    let mut single = NonEmptyBitFlags::from(Test::C);
+    assert_eq!(single.exactly_one(), Some(Test::C));
+    single.insert(Test::A);
+    assert_eq!(BitFlags::from(single), Test::A | Test::C);
+}
+
+#[test]
+fn const_flags() {
+    use enumflags2::{BitFlags, ConstFlags};
+
+    const A: u128 = enumflags2::make_bitflags!(Test::A as u128);
+    const AB: u128 = enumflags2::make_bitflags!(Test::{A | B} as u128);
+    const B: u128 = enumflags2::make_bitflags!(Test::B as u128);
+
+    let flags = ConstFlags::<Test, A>::new();
+    assert!(flags.contains::<A>() && !flags.contains::<AB>());
+    let flags = flags.insert::<B, AB>();
+    flags.require::<AB>();
+    assert_eq!(flags.flags(), Test::A | Test::B);
+    let flags = flags.remove::<A, B>();
+    assert_eq!(BitFlags::from(flags), Test::B);
+    assert_eq!(ConstFlags::<Test, AB>::BITS, 0b11);
+}
+
+#[test]
+fn subset_projection() {
+    use enumflags2::BitFlags;
+
+    let sub = SubTest::B | SubTest::D;
+    assert_eq!(sub.widen::<Test>(), Test::B | Test::D);
+    assert_eq!(BitFlags::<SubTest>::empty().widen::<Test>(), BitFlags::empty());
+
+    let flags = Test::A | Test::B;
+    assert_eq!(flags.project::<SubTest>(), SubTest::B);
+    let error = flags.try_project::<SubTest>().unwrap_err();
+    assert_eq!(error.unexpected(), Test::A);
+    assert_eq!(error.truncate(), Test::B);
+    assert_eq!(BitFlags::from(Test::D).try_project::<SubTest>().unwrap(), SubTest::D);
+}
+
+#[test]
+fn translations() {
+    use enumflags2::BitFlags;
+
+    assert_eq!(BitFlags::<Test>::all().translate::<Shuffled>(), Shuffled::A | Shuffled::B | Shuffled::D);
+    assert_eq!((Test::A | Test::D).try_translate::<Shuffled>().unwrap(), Shuffled::A | Shuffled::D);
+    assert_eq!((Test::A | Test::C).try_translate::<Shuffled>().unwrap_err().unexpected(), Test::C);
+
+    let flags = Shuffled::B | Shuffled::E;
+    assert_eq!(flags.translate::<Test>(), Test::B);
+    assert_eq!(flags.try_translate::<Test>().unwrap_err().truncate(), Shuffled::B);
+    assert_eq!(BitFlags::<Shuffled>::empty().translate::<Test>(), BitFlags::empty());
+}
+
+#[test]
+fn layouts() {
+    use enumflags2::{BitFlag, FlagLayout};
+
+    const LAYOUT: FlagLayout<'static> = FlagLayout::new(&[("A", 1), ("B", 2), ("C", 4), ("D", 8)]);
+    const FINGERPRINT: u64 = LAYOUT.fingerprint();
+
+    assert_eq!(Test::LAYOUT, [("A", 1), ("B", 2), ("C", 4), ("D", 8)]);
+    assert_eq!(FlagLayout::of::<Test1>().get("E"), Some(1 << 34));
+    assert_eq!(FlagLayout::of::<Test1>().get("F"), None);
+    assert_eq!(FlagLayout::of::<Test>().fingerprint(), FINGERPRINT);
+    assert_ne!(FlagLayout::of::<SubTest>().fingerprint(), FINGERPRINT);
+}
+
+#[test]
+fn versioned_bits() {
+    use enumflags2::BitFlags;
+
+    let from_version = |bits, version| BitFlags::<Renumbered>::from_bits_versioned(bits, version);
+    assert_eq!(from_version(0b001_0001, 0).unwrap(), Renumbered::A | Renumbered::B);
+    assert_eq!(from_version(0b000_0110, 2).unwrap(), Renumbered::B | Renumbered::C);
+    assert_eq!(from_version(0b000_0100, 2).unwrap(), Renumbered::B);
+    assert_eq!(from_version(0b000_0100, 3).unwrap(), Renumbered::C);
+    assert_eq!(from_version(0b000_0100, 7).unwrap(), Renumbered::C);
+
+    let error = from_version(0b000_1001, 1).unwrap_err();
+    assert_eq!(error.invalid_bits(), 0b1000);
+    assert_eq!(error.truncate(), Renumbered::A);
+    assert_eq!(error.legacy_layout(), None);
+
+    let error = BitFlags::<Renumbered>::from_bits(0b001_0001).unwrap_err();
+    assert_eq!(error.legacy_layout(), Some(1));
+    let error = BitFlags::<Renumbered>::from_bits(0b1000_0001).unwrap_err();
+    assert_eq!(error.legacy_layout(), None);
+}
+
+#[test]
+fn symbolic() {
+    use enumflags2::BitFlags;
+
+    assert_eq!(BitFlags::<Renamed>::from_symbolic("r-x").unwrap(), Renamed::ReadOnly | Renamed::Execute);
+    assert_eq!(BitFlags::<Renamed>::from_symbolic("---").unwrap(), BitFlags::empty());
+    assert_eq!(BitFlags::<Test>::from_symbolic("AB-D").unwrap(), Test::A | Test::B | Test::D);
+    assert_eq!(
+        BitFlags::<Test>::from_symbolic_with_placeholder("..C.", '.').unwrap(),
+        Test::C
+    );
+
+    assert_eq!(BitFlags::<Renamed>::from_symbolic("rxw").unwrap_err().column(), 1);
+    assert_eq!(BitFlags::<Renamed>::from_symbolic("rw").unwrap_err().column(), 2);
+    assert_eq!(BitFlags::<Renamed>::from_symbolic("rwx-").unwrap_err().column(), 3);
+    assert_eq!(BitFlags::<Test>::from_symbolic("A-D-").unwrap_err().column(), 2);
+    assert_eq!(
+        BitFlags::<Renamed>::from_symbolic_with_placeholder("r-x", 'x').unwrap_err().column(),
+        2
+    );
+}
+
+#[test]
+fn labels() {
+    use enumflags2::BitFlag;
+
+    assert_eq!(Renamed::ReadOnly.label(), "read-only");
+    assert_eq!(Renamed::ReadWrite.label(), "Read and write");
+    assert_eq!(Renamed::Execute.label(), "execute");
+    assert_eq!(Renamed::ReadOnly.description(), Some("Only allows reading."));
+    assert_eq!(
+        Renamed::ReadWrite.description(),
+        Some("Allows reading and writing.\n\nImplies `ReadOnly`.")
+    );
+    assert_eq!(Renamed::Execute.description(), None);
+    assert_eq!(Test::A.label(), "A");
+    assert_eq!(Test::A.description(), None);
+}
+
+#[test]
+fn flag_from_str() {
+    use enumflags2::ParseFlagError;
+
+    assert_eq!("read-only".parse(), Ok(Renamed::ReadOnly));
+    assert_eq!("rw".parse(), Ok(Renamed::ReadWrite));
+    assert_eq!("ReadWrite".parse(), Ok(Renamed::ReadWrite));
+    assert_eq!("exec".parse(), Ok(Renamed::Execute));
+    assert_eq!("ReadOnly".parse::<Renamed>(), Err(ParseFlagError));
+    assert_eq!("".parse::<Renamed>(), Err(ParseFlagError));
+}
+
+#[test]
+fn accessors() {
+    use enumflags2::BitFlags;
+
+    let mut flags = BitFlags::<Renamed>::empty().with_read_only();
+    assert!(flags.is_read_only());
+    assert!(!flags.is_read_write());
+    flags.set_execute(true);
+    flags.set_read_only(false);
+    assert_eq!(flags, Renamed::Execute);
+    assert_eq!(flags.with_execute(), Renamed::Execute);
+
+    let renumbered = BitFlags::<Renumbered>::all();
+    assert!(renumbered.is_a() && renumbered.is_b() && renumbered.is_c());
+}
+
+#[test]
+fn bools() {
+    use enumflags2::BitFlags;
+
+    let bools = TestBools {
+        a: true,
+        second: true,
+        c: false,
+        d: true,
+    };
+    assert_eq!(BitFlags::from(bools), Test::A | Test::B | Test::D);
+    assert_eq!(TestBools::from(BitFlags::from(bools)), bools);
+    assert!(TestBools::from(BitFlags::from(Test::C)).c);
+
+    let renamed = RenamedBools {
+        read_write: true,
+        ..RenamedBools::default()
+    };
+    assert_eq!(BitFlags::from(renamed), Renamed::ReadWrite);
+    assert_eq!(RenamedBools::from(BitFlags::all()), RenamedBools {
+        read_only: true,
+        read_write: true,
+        execute: true,
+    });
+}
@@ -14,8 +14,17 @@ pub enum Test {
     D = 1 << 3,
 }
 
+#[bitflags(display, from_str, accessors, bools, try_from)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[repr(u8)]
+pub enum Options {
+    A = 1 << 0,
+    B = 1 << 1,
+}
+
 #[test]
 fn test_foo() {
     // assert!() doesn't even work in no_implicit_prelude!
     let _ = BitFlags::<Test>::all();
+    let _ = BitFlags::<Options>::all();
 }
@@ -0,0 +1,49 @@
+use enumflags2::{bitflags, BitFlags, ParallelBitFlagsIterator};
+use rayon::prelude::*;
+
+#[bitflags]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[repr(u16)]
+enum Test {
+    A = 1 << 0,
+    B = 1 << 1,
+    C = 1 << 2,
+    D = 1 << 9,
+}
+
+#[test]
+fn collect() {
+    let flags: BitFlags<Test> = [Test::A, Test::D].par_iter().collect();
+    assert_eq!(flags, Test::A | Test::D);
+
+    let flags: BitFlags<Test> = (0..1000u16)
+        .into_par_iter()
+        .map(BitFlags::from_bits_truncate)
+        .collect();
+    assert!(flags.is_all());
+}
+
+#[test]
+fn extend() {
+    let mut flags = BitFlags::from_flag(Test::B);
+    flags.par_extend(vec![Test::C | Test::D]);
+    assert_eq!(flags, Test::B | Test::C | Test::D);
+}
+
+#[test]
+fn reductions() {
+    let sets: Vec<BitFlags<Test>> = vec![
+        Test::A | Test::B | Test::C,
+        Test::B | Test::C,
+        Test::C | Test::D,
+    ];
+
+    let union: BitFlags<Test> = sets.par_iter().copied().union();
+    assert!(union.is_all());
+
+    let intersection: BitFlags<Test> = sets.par_iter().copied().intersection();
+    assert_eq!(intersection, Test::C);
+
+    let empty: BitFlags<Test> = Vec::<Test>::new().into_par_iter().intersection();
+    assert!(empty.is_all());
+}
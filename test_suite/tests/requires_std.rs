@@ -80,6 +80,274 @@ fn display_format() {
     );
 }
 
+#[test]
+fn display_builder() {
+    let flags = Test::A | Test::C;
+    assert_eq!(flags.display().to_string(), flags.to_string());
+    assert_eq!(flags.display().separator(",").uppercase().to_string(), "A,C");
+    assert_eq!(flags.display().lowercase().hex().to_string(), "a | c (0x5)");
+    assert_eq!(flags.display().braces().separator(" ").to_string(), "{A C}");
+    assert_eq!(BitFlags::<Test>::empty().display().empty("none").to_string(), "none");
+    assert_eq!(BitFlags::<Test>::empty().display().binary().to_string(), "<empty> (0b0)");
+    assert_eq!(format!("{:?}", flags.display()), "\"A | C\"");
+
+    assert_eq!(format!("[{:8}]", flags.display()), "[A | C   ]");
+    assert_eq!(format!("[{:*^9}]", flags.display()), "[**A | C**]");
+    assert_eq!(format!("[{:>2}]", flags.display()), "[A | C]");
+
+    let renamed = Renamed::ReadOnly | Renamed::ReadWrite;
+    assert_eq!(renamed.display().uppercase().to_string(), "READ-ONLY | RW");
+}
+
+#[test]
+fn explain_format() {
+    assert_eq!(
+        BitFlags::<Renamed>::all().explain().to_string(),
+        "read-only       Only allows reading.\n\
+         Read and write  Allows reading and writing.\n\
+         \n                \
+         Implies `ReadOnly`.\n\
+         execute"
+    );
+    assert_eq!(BitFlags::<Test>::all().explain().to_string(), "A\nB\nC\nD");
+    assert_eq!(BitFlags::<Test>::empty().explain().to_string(), "");
+}
+
+#[test]
+fn format_without_debug() {
+    #[bitflags(display, from_str)]
+    #[derive(Copy, Clone, PartialEq)]
+    #[repr(u8)]
+    enum Plain {
+        A = 1 << 0,
+        #[bitflag(rename = "bee")]
+        B = 1 << 1,
+    }
+
+    let flags = Plain::A | Plain::B;
+    assert_eq!(flags.to_string(), "A | bee");
+    assert_eq!(format!("{:?}", flags), "BitFlags<Plain>(0b11, A | B)");
+    assert_eq!(format!("[{:>5}]", Plain::B), "[  bee]");
+    assert!("bee".parse::<Plain>() == Ok(Plain::B));
+    assert_eq!("B".parse::<Plain>().err().unwrap().to_string(), "unknown flag name");
+    assert_eq!(
+        BitFlags::<Plain>::from_bits(0b100).unwrap_err().to_string(),
+        "Invalid bits for BitFlags<Plain>(0b0): bit 2"
+    );
+}
+
+#[test]
+fn renamed_format() {
+    use enumflags2::FlagsPatch;
+
+    assert_eq!(BitFlags::<Renamed>::all().to_string(), "read-only | rw | execute");
+    assert_eq!(
+        format!("{:?}", BitFlags::<Renamed>::all()),
+        "BitFlags<Renamed>(0b111, ReadOnly | ReadWrite | Execute)"
+    );
+
+    let patch = FlagsPatch::new(Renamed::ReadWrite, Renamed::Execute);
+    assert_eq!(patch.to_string(), "+rw -execute");
+    for source in &["+rw -execute", "+read-write -exec", "+ReadWrite -execute"] {
+        assert_eq!(source.parse::<FlagsPatch<Renamed>>().unwrap(), patch);
+    }
+    assert!("+ReadOnly".parse::<FlagsPatch<Renamed>>().is_err());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn renamed_predicate() {
+    use enumflags2::Predicate;
+
+    let predicate: Predicate<Renamed> = "read-only & !(rw | exec)".parse().unwrap();
+    assert!(predicate.matches(Renamed::ReadOnly));
+    assert!(!predicate.matches(Renamed::ReadOnly | Renamed::Execute));
+}
+
+#[test]
+fn symbolic_format() {
+    assert_eq!(BitFlags::<Renamed>::all().symbolic().to_string(), "rwx");
+    assert_eq!(BitFlags::from_flag(Renamed::ReadWrite).symbolic().to_string(), "-w-");
+    assert_eq!(BitFlags::<Test>::empty().symbolic().placeholder('.').unwrap().to_string(), "....");
+    let error = BitFlags::<Test>::empty().symbolic().placeholder('C').unwrap_err();
+    assert_eq!(error.column(), 2);
+    assert_eq!(error.to_string(), "placeholder `C` is also the symbol at column 2");
+    assert_eq!(format!("{:?}", (Test::A | Test::C).symbolic()), "\"A-C-\"");
+
+    let error = |s: &str| BitFlags::<Renamed>::from_symbolic(s).unwrap_err().to_string();
+    assert_eq!(error("rxw"), "expected `w` or `-` at column 1, found `x`");
+    assert_eq!(error("rw"), "expected `x` or `-` at column 2, found end of input");
+    assert_eq!(error("rwx-"), "expected end of input at column 3");
+}
+
+#[test]
+fn symbolic_dash_name() {
+    #[bitflags(display)]
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    #[repr(u8)]
+    enum Options {
+        #[bitflag(rename = "-verbose")]
+        Verbose = 1 << 0,
+        #[bitflag(rename = "-quiet", symbol = 'q')]
+        Quiet = 1 << 1,
+    }
+
+    assert_eq!(BitFlags::from_flag(Options::Verbose).to_string(), "-verbose");
+    assert_eq!(BitFlags::from_flag(Options::Quiet).symbolic().to_string(), "-q");
+
+    let error = BitFlags::<Options>::from_symbolic("-q").unwrap_err();
+    assert_eq!(error.column(), 0);
+    assert_eq!(error.to_string(), "placeholder `-` is also the symbol at column 0");
+    assert_eq!(
+        BitFlags::<Options>::from_symbolic_with_placeholder("-.", '.').unwrap(),
+        Options::Verbose
+    );
+}
+
+#[test]
+fn patch_format() {
+    use enumflags2::FlagsPatch;
+
+    assert_eq!(
+        FlagsPatch::new(Test::A | Test::D, Test::B).to_string(),
+        "+A +D -B"
+    );
+    assert_eq!(FlagsPatch::<Test>::empty().to_string(), "<empty>");
+    assert_eq!(
+        "+E".parse::<FlagsPatch<Test>>().unwrap_err().to_string(),
+        "unknown flag name at position 1"
+    );
+}
+
+#[test]
+fn snake_case_underscores() {
+    #[bitflags(rename_all = "snake_case", accessors)]
+    #[allow(non_camel_case_types)]
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    #[repr(u8)]
+    enum Path {
+        Fast_Path = 1 << 0,
+        SlowPath = 1 << 1,
+    }
+
+    // rename_all adds an underscore before every uppercase letter, but
+    // the accessors don't double existing ones.
+    let flags = Path::Fast_Path | Path::SlowPath;
+    assert_eq!(flags.to_string(), "fast__path | slow_path");
+    assert!(flags.is_fast_path() && flags.is_slow_path());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn predicates() {
+    use enumflags2::{flag_expr, Predicate};
+
+    let cases = [
+        ("A", flag_expr!(Test, A) as fn(BitFlags<Test>) -> bool),
+        ("!A", flag_expr!(Test, !A)),
+        ("A | B & C", flag_expr!(Test, A | B & C)),
+        ("(A | B) & !(C ^ D)", flag_expr!(Test, (A | B) & !(C ^ D))),
+        ("!(A & B | !C) ^ !!D", flag_expr!(Test, !(A & B | !C) ^ !!D)),
+        ("A & !A", flag_expr!(Test, A & !A)),
+        ("A | !A", flag_expr!(Test, A | !A)),
+    ];
+
+    for (source, expected) in cases.iter() {
+        let predicate: Predicate<Test> = source.parse().unwrap();
+        for flags in BitFlags::<Test>::all().subsets() {
+            assert_eq!(predicate.matches(flags), expected(flags), "{} for {:?}", source, flags);
+        }
+    }
+
+    let error = |source: &str| {
+        let error = source.parse::<Predicate<Test>>().unwrap_err();
+        (error.span(), error.to_string())
+    };
+
+    assert_eq!(error(""), (0..0, "unexpected end of expression at 0..0".to_owned()));
+    assert_eq!(error("A |"), (3..3, "unexpected end of expression at 3..3".to_owned()));
+    assert_eq!(error("A | Foo"), (4..7, "unknown flag name at 4..7".to_owned()));
+    assert_eq!(error("(A | B"), (6..6, "unexpected end of expression at 6..6".to_owned()));
+    assert_eq!(error("A B"), (2..3, "unexpected token at 2..3".to_owned()));
+    assert_eq!(error("A + B"), (2..3, "unexpected token at 2..3".to_owned()));
+
+    let nested = format!("{}A{}", "(".repeat(200_000), ")".repeat(200_000));
+    assert_eq!(error(&nested), (64..65, "expression nested too deeply at 64..65".to_owned()));
+    let negated = format!("{}A", "!".repeat(200_000));
+    assert_eq!(error(&negated).1, "expression nested too deeply at 64..65");
+
+    let long = vec!["A"; 200_000].join(" & ");
+    assert!(long.parse::<Predicate<Test>>().unwrap().matches(Test::A));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn predicate_limits() {
+    use enumflags2::Predicate;
+
+    #[bitflags]
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    #[repr(u32)]
+    enum Wide {
+        F0 = 1 << 0,
+        F1 = 1 << 1,
+        F2 = 1 << 2,
+        F3 = 1 << 3,
+        F4 = 1 << 4,
+        F5 = 1 << 5,
+        F6 = 1 << 6,
+        F7 = 1 << 7,
+        F8 = 1 << 8,
+        F9 = 1 << 9,
+        F10 = 1 << 10,
+        F11 = 1 << 11,
+        F12 = 1 << 12,
+        F13 = 1 << 13,
+        F14 = 1 << 14,
+        F15 = 1 << 15,
+        F16 = 1 << 16,
+        F17 = 1 << 17,
+        F18 = 1 << 18,
+        F19 = 1 << 19,
+        F20 = 1 << 20,
+        F21 = 1 << 21,
+        F22 = 1 << 22,
+        F23 = 1 << 23,
+        F24 = 1 << 24,
+        F25 = 1 << 25,
+        F26 = 1 << 26,
+        F27 = 1 << 27,
+        F28 = 1 << 28,
+        F29 = 1 << 29,
+    }
+
+    let xor = |n: usize| {
+        (0..n)
+            .map(|i| format!("F{}", i))
+            .collect::<Vec<_>>()
+            .join(" ^ ")
+    };
+
+    let parity: Predicate<Wide> = xor(8).parse().unwrap();
+    assert_eq!(parity.patterns().len(), 128);
+    assert!(parity.matches(Wide::F0 | Wide::F3 | Wide::F5));
+    assert!(!parity.matches(Wide::F0 | Wide::F3));
+
+    for &n in &[12, 30] {
+        let source = xor(n);
+        let error = source.parse::<Predicate<Wide>>().unwrap_err();
+        assert_eq!(error.span(), 0..source.len());
+        assert!(error.to_string().starts_with("expression too complex"));
+    }
+
+    let wide = (0..15)
+        .map(|i| format!("(F{} | F{})", 2 * i, 2 * i + 1))
+        .collect::<Vec<_>>()
+        .join(" & ");
+    let error = wide.parse::<Predicate<Wide>>().unwrap_err();
+    assert!(error.to_string().starts_with("expression too complex"));
+}
+
 #[test]
 fn format() {
     // Assert BitFlags<T> impls fmt::{Binary, Octal, LowerHex, UpperHex}
@@ -120,3 +388,101 @@ fn works_in_maps_generic<T: BitFlag>() {
     let mut map: HashSet<BitFlags<T>> = HashSet::new();
     map.insert(BitFlags::empty());
 }
+
+#[test]
+fn from_bits_error_format() {
+    #[bitflags(default = A, reserved(Legacy = 1 << 4, Wide = 0b1100_0000))]
+    #[derive(Copy, Clone, Debug)]
+    #[repr(u8)]
+    enum Reserved {
+        A = 1 << 0,
+        B = 1 << 1,
+    }
+
+    let error = BitFlags::<Reserved>::from_bits(0b1101_1001).unwrap_err();
+    assert_eq!(error.valid_bits(), 0b1);
+    assert_eq!(
+        error.to_string(),
+        "Invalid bits for BitFlags<Reserved>(0b1, A): bit 3, bit 4 (Legacy), bit 6 (Wide), bit 7 (Wide)"
+    );
+
+    let error: Box<dyn std::error::Error> = Box::new(error);
+    assert!(error.source().is_none());
+}
+
+#[test]
+fn non_empty_conversions() {
+    use enumflags2::NonEmptyBitFlags;
+    use std::convert::TryFrom;
+    use std::mem::size_of;
+
+    assert_eq!(size_of::<Option<NonEmptyBitFlags<Test>>>(), size_of::<u8>());
+    assert_eq!(size_of::<Option<NonEmptyBitFlags<Test1>>>(), size_of::<u64>());
+
+    let flags = NonEmptyBitFlags::try_from(Test::A | Test::C).unwrap();
+    assert_eq!(format!("{}", flags), "A | C");
+    let error = NonEmptyBitFlags::try_from(BitFlags::<Test>::empty()).unwrap_err();
+    assert_eq!(error.to_string(), "expected at least one flag to be set");
+
+}
+
+#[test]
+fn single_flag_conversions() {
+    use std::convert::TryFrom;
+
+    #[bitflags(try_from)]
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    #[repr(u8)]
+    enum Single {
+        A = 1 << 0,
+        B = 1 << 1,
+        C = 1 << 2,
+    }
+
+    assert_eq!(Single::try_from(BitFlags::from(Single::C)), Ok(Single::C));
+    let error = Single::try_from(Single::A | Single::C).unwrap_err();
+    assert_eq!(error.flags(), Single::A | Single::C);
+    assert_eq!(error.to_string(), "expected exactly one flag, found A | C");
+    assert!(Single::try_from(BitFlags::empty()).is_err());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn saved_layouts() {
+    use enumflags2::{FlagLayout, LayoutChange, SavedLayout};
+
+    let layout = FlagLayout::of::<Test>();
+    let saved = SavedLayout::from(layout);
+    assert_eq!(saved.fingerprint(), layout.fingerprint());
+    assert!(layout.changes_since(&saved).is_empty());
+
+    let old = SavedLayout::from(FlagLayout::new(&[("A", 1), ("B", 4), ("X", 8)]));
+    assert_eq!(old.flags()[2], ("X".to_owned(), 8));
+    let diff = layout.changes_since(&old);
+    assert!(!diff.is_compatible());
+    assert_eq!(
+        diff.changes()[0],
+        LayoutChange::Moved {
+            name: "B".to_owned(),
+            old: 4,
+            new: 2,
+        }
+    );
+    assert_eq!(diff.changes().len(), 4);
+}
+
+#[test]
+fn versioned_bits_error_format() {
+    let error = BitFlags::<Renumbered>::from_bits(0b001_0001).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Invalid bits for BitFlags<Renumbered>(0b1, A): bit 4 (B before version 1); \
+         valid in the layout used before version 1",
+    );
+
+    let error = BitFlags::<Renumbered>::from_bits(0b1000_0000).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Invalid bits for BitFlags<Renumbered>(0b0): bit 7 (Spare)",
+    );
+}
@@ -1,4 +1,4 @@
-use enumflags2::{bitflags, BitFlags};
+use enumflags2::{bitflags, BitFlags, FlagsPatch};
 use serde::{Deserialize, Serialize};
 
 #[test]
@@ -18,5 +18,200 @@ fn serde_compile() {
     #[derive(Clone, Debug, Serialize, Deserialize)]
     struct TestStructContainsFlags {
         flags: TestBitFlags,
+        patch: FlagsPatch<Test>,
     }
 }
+
+#[test]
+fn serde_versioned() {
+    use enumflags2::FromVersion;
+    use serde::de::value::{Error, MapDeserializer, U8Deserializer};
+    use serde::de::{DeserializeSeed, IntoDeserializer};
+
+    #[bitflags]
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    #[repr(u8)]
+    enum Test {
+        A = 1 << 0,
+        #[bitflag(previously = 1 << 3, until = 2)]
+        B = 1 << 1,
+    }
+
+    #[derive(Deserialize)]
+    struct OldConfig {
+        #[serde(deserialize_with = "enumflags2::deserialize_from_version::<1, _, _>")]
+        flags: BitFlags<Test>,
+    }
+
+    let deserializer = |bits: u8| -> U8Deserializer<Error> { bits.into_deserializer() };
+    let seed = || FromVersion::<Test>::new(1);
+    assert_eq!(seed().deserialize(deserializer(0b1001)).unwrap(), Test::A | Test::B);
+    assert!(seed().deserialize(deserializer(0b0010)).is_err());
+    assert_eq!(
+        FromVersion::<Test>::new(2).deserialize(deserializer(0b0010)).unwrap(),
+        Test::B
+    );
+
+    let fields = std::iter::once(("flags", 0b1000u8));
+    let config = OldConfig::deserialize(MapDeserializer::<_, Error>::new(fields)).unwrap();
+    assert_eq!(config.flags, Test::B);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn serde_layouts() {
+    use enumflags2::{FlagLayout, SavedLayout};
+
+    #[bitflags]
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    #[repr(u8)]
+    enum Test {
+        A = 1 << 0,
+        B = 1 << 1,
+    }
+
+    let layout = FlagLayout::of::<Test>();
+    let json = serde_json::to_string(&layout).unwrap();
+    assert_eq!(
+        json,
+        format!(
+            r#"{{"fingerprint":"{:016x}","flags":[{{"name":"A","bits":"0x1"}},{{"name":"B","bits":"0x2"}}]}}"#,
+            layout.fingerprint()
+        )
+    );
+
+    let saved: SavedLayout = serde_json::from_str(&json).unwrap();
+    assert_eq!(saved, SavedLayout::from(layout));
+    assert_eq!(serde_json::to_string(&saved).unwrap(), json);
+    assert!(layout.changes_since(&saved).is_empty());
+
+    let parse = |json: &str| serde_json::from_str::<SavedLayout>(json);
+    let old = parse(r#"{"flags": [{"bits": "0x1", "name": "A"}, {"name": "B", "bits": "0x4"}]}"#).unwrap();
+    assert_eq!(old.flags()[1], ("B".to_owned(), 4));
+    assert!(!layout.changes_since(&old).is_compatible());
+
+    let error = |json: &str| parse(json).unwrap_err().to_string();
+    assert!(error(r#"{"flags": [], "fingerprint": "1"}"#)
+        .starts_with("fingerprint doesn't match the flags"));
+    assert!(error(r#"{"flags": [], "fingerprint": "xyz"}"#)
+        .starts_with("invalid value: string \"xyz\", expected a hexadecimal fingerprint"));
+    assert!(error(r#"{"flags": [{"name": "A"}]}"#).starts_with("missing field `bits`"));
+    assert!(error(r#"{"flags": [{"name": "A", "bits": 1}]}"#).starts_with("invalid type"));
+    assert!(error(r#"{"flags": [{"name": "A", "bits": "1"}]}"#).starts_with("invalid value"));
+    assert!(error(r#"{"flag": []}"#).starts_with("unknown field `flag`"));
+    assert!(error(r#"{"flags": [], "flags": []}"#).starts_with("duplicate field `flags`"));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn serde_layouts_non_self_describing() {
+    use enumflags2::{FlagLayout, SavedLayout};
+
+    #[bitflags]
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    #[repr(u128)]
+    enum Test {
+        A = 1 << 0,
+        B = 1 << 127,
+    }
+
+    let layout = FlagLayout::of::<Test>();
+    let bytes = bincode::serialize(&layout).unwrap();
+    let saved: SavedLayout = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(saved, SavedLayout::from(layout));
+    assert_eq!(bincode::serialize(&saved).unwrap(), bytes);
+    assert!(layout.changes_since(&saved).is_empty());
+
+    let mut tampered = bytes.clone();
+    *tampered.last_mut().unwrap() ^= 1;
+    assert!(bincode::deserialize::<SavedLayout>(&tampered).is_err());
+}
+
+#[test]
+fn serde_names() {
+    #[bitflags(rename_all = "snake_case")]
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    #[repr(u8)]
+    enum Test {
+        ReadOnly = 1 << 0,
+        #[bitflag(rename = "rw", alias = "read_write")]
+        ReadWrite = 1 << 1,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Config {
+        #[serde(
+            serialize_with = "enumflags2::serialize_names",
+            deserialize_with = "enumflags2::deserialize_names"
+        )]
+        flags: BitFlags<Test>,
+    }
+
+    let config = Config {
+        flags: BitFlags::all(),
+    };
+    let json = serde_json::to_string(&config).unwrap();
+    assert_eq!(json, r#"{"flags":["read_only","rw"]}"#);
+    assert_eq!(serde_json::from_str::<Config>(&json).unwrap(), config);
+
+    let parse = |json: &str| serde_json::from_str::<Config>(json).map(|config| config.flags);
+    assert_eq!(parse(r#"{"flags":["read_write"]}"#).unwrap(), Test::ReadWrite);
+    assert_eq!(parse(r#"{"flags":[]}"#).unwrap(), BitFlags::empty());
+    assert!(parse(r#"{"flags":["ReadOnly"]}"#).is_err());
+}
+
+#[test]
+fn serde_bools() {
+    #[bitflags(rename_all = "kebab-case", bools)]
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    #[repr(u8)]
+    enum Test {
+        Read = 1 << 0,
+        #[bitflag(alias = "modify")]
+        ReadWrite = 1 << 1,
+    }
+
+    let bools = TestBools {
+        read: true,
+        read_write: false,
+    };
+    let json = serde_json::to_string(&bools).unwrap();
+    assert_eq!(json, r#"{"read":true,"read-write":false}"#);
+    assert_eq!(serde_json::from_str::<TestBools>(&json).unwrap(), bools);
+
+    let parse = |json: &str| serde_json::from_str::<TestBools>(json).map(BitFlags::from);
+    assert_eq!(parse(r#"{"read": true}"#).unwrap(), Test::Read);
+    assert_eq!(parse(r#"{"modify": true, "read": false}"#).unwrap(), Test::ReadWrite);
+    assert_eq!(parse("{}").unwrap(), BitFlags::empty());
+    assert!(parse(r#"{"write": true}"#).is_err());
+    assert!(parse(r#"{"read": true, "read": false}"#).is_err());
+    assert!(parse(r#"{"read": 1}"#).is_err());
+}
+
+#[test]
+fn serde_bools_field_names() {
+    #[bitflags(bools)]
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    #[repr(u8)]
+    enum Test {
+        Read = 1 << 0,
+        ReadWrite = 1 << 1,
+        #[bitflag(rename = "exec")]
+        Execute = 1 << 2,
+    }
+
+    let bools = TestBools {
+        read: false,
+        read_write: true,
+        execute: true,
+    };
+    let json = serde_json::to_string(&bools).unwrap();
+    assert_eq!(json, r#"{"read":false,"read_write":true,"exec":true}"#);
+    assert_eq!(serde_json::from_str::<TestBools>(&json).unwrap(), bools);
+
+    let parse = |json: &str| serde_json::from_str::<TestBools>(json).map(BitFlags::from);
+    assert_eq!(parse(r#"{"read_write": true}"#).unwrap(), Test::ReadWrite);
+    assert_eq!(parse(r#"{"ReadWrite": true}"#).unwrap(), Test::ReadWrite);
+    assert!(parse(r#"{"read_write": true, "ReadWrite": false}"#).is_err());
+    assert!(parse(r#"{"execute": true}"#).is_err());
+}
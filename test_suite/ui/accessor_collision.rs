@@ -0,0 +1,19 @@
+use enumflags2::bitflags;
+
+#[bitflags(accessors)]
+#[derive(Copy, Clone)]
+#[repr(u8)]
+#[allow(non_camel_case_types)]
+enum Collision {
+    FastPath = 1 << 0,
+    Fast_Path = 1 << 1,
+}
+
+#[bitflags(accessors)]
+#[derive(Copy, Clone)]
+#[repr(u8)]
+enum Shadowed {
+    Empty = 1 << 0,
+}
+
+fn main() {}
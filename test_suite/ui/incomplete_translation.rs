@@ -0,0 +1,25 @@
+#[enumflags2::bitflags]
+#[derive(Copy, Clone)]
+#[repr(u8)]
+enum Internal {
+    Read = 1 << 0,
+    Write = 1 << 1,
+}
+
+#[enumflags2::bitflags]
+#[derive(Copy, Clone)]
+#[repr(u32)]
+enum Wire {
+    Read = 1 << 5,
+    Write = 1 << 3,
+    Sticky = 1 << 9,
+}
+
+enumflags2::translate_flags! {
+    Internal <=> Wire {
+        Read,
+        Read => Write,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+use enumflags2::IntoBitFlags;
+
+#[derive(IntoBitFlags)]
+struct NoFlags {
+    read: bool,
+}
+
+#[derive(IntoBitFlags)]
+#[bitflag(flags = Perm)]
+struct NotBool {
+    read: u8,
+}
+
+#[derive(IntoBitFlags)]
+#[bitflag(flags = Perm)]
+struct Tuple(bool);
+
+fn main() {}
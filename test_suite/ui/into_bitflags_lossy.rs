@@ -0,0 +1,29 @@
+use enumflags2::{bitflags, IntoBitFlags};
+
+#[bitflags]
+#[derive(Copy, Clone)]
+#[repr(u8)]
+enum Perm {
+    Read = 1 << 0,
+    Write = 1 << 1,
+    Execute = 1 << 2,
+}
+
+#[derive(IntoBitFlags)]
+#[bitflag(flags = Perm)]
+struct Missing {
+    read: bool,
+    write: bool,
+}
+
+#[derive(IntoBitFlags)]
+#[bitflag(flags = Perm)]
+struct Duplicate {
+    read: bool,
+    write: bool,
+    execute: bool,
+    #[bitflag(flag = Write)]
+    writable: bool,
+}
+
+fn main() {}
@@ -0,0 +1,50 @@
+use enumflags2::bitflags;
+
+#[bitflags(rename_all = "Title Case")]
+#[repr(u8)]
+enum UnknownCase {
+    A = 1,
+    B = 2,
+}
+
+#[bitflags(rename_all = "lowercase")]
+#[repr(u8)]
+enum Collision {
+    ReadOnly = 1,
+    #[bitflag(rename = "readonly")]
+    ReadOnly2 = 2,
+}
+
+#[bitflags]
+#[repr(u8)]
+enum AliasCollision {
+    A = 1,
+    #[bitflag(alias = "A")]
+    B = 2,
+}
+
+#[bitflags]
+#[repr(u8)]
+enum DuplicateRename {
+    A = 1,
+    #[bitflag(rename = "b", rename = "c")]
+    B = 2,
+}
+
+#[bitflags]
+#[repr(u8)]
+enum UnknownParameter {
+    A = 1,
+    #[bitflag(name = "b")]
+    B = 2,
+}
+
+#[bitflags]
+#[repr(u8)]
+enum PlaceholderSymbol {
+    A = 1,
+    #[bitflag(symbol = '-')]
+    B = 2,
+}
+
+fn main() {}
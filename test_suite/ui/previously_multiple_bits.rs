@@ -0,0 +1,10 @@
+#[enumflags2::bitflags]
+#[derive(Copy, Clone)]
+#[repr(u8)]
+enum Foo {
+    #[bitflag(previously = 0b1100, until = 2)]
+    A = 1 << 0,
+    B = 1 << 1,
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+#[enumflags2::bitflags]
+#[derive(Copy, Clone)]
+#[repr(u8)]
+enum Foo {
+    #[bitflag(previously = 1 << 0, until = 2)]
+    A = 1 << 4,
+    B = 1 << 0,
+}
+
+#[enumflags2::bitflags]
+#[derive(Copy, Clone)]
+#[repr(u8)]
+enum Swapped {
+    #[bitflag(previously = 1 << 1, until = 3)]
+    A = 1 << 0,
+    #[bitflag(previously = 1 << 0, until = 3)]
+    B = 1 << 1,
+    C = 1 << 2,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+#[enumflags2::bitflags]
+#[derive(Copy, Clone)]
+#[repr(u8)]
+enum Foo {
+    A = 1 << 0,
+    #[bitflag(previously = 1 << 4)]
+    B = 1 << 1,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+#[enumflags2::bitflags(reserved(Taken = 1 << 1, Spare = 1 << 4, Again = 0b0011_0000))]
+#[derive(Copy, Clone)]
+#[repr(u8)]
+enum Foo {
+    A = 1 << 0,
+    B = 1 << 1,
+}
+
+#[enumflags2::bitflags(reserved(TooWide = 1 << 8))]
+#[derive(Copy, Clone)]
+#[repr(u8)]
+enum Bar {
+    A = 1 << 0,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+#[enumflags2::bitflags]
+#[derive(Copy, Clone)]
+#[repr(u8)]
+enum Super {
+    A = 1 << 0,
+    B = 1 << 1,
+}
+
+#[enumflags2::bitflags(subset_of = Super)]
+#[derive(Copy, Clone)]
+#[repr(u8)]
+enum Sub {
+    B = 1 << 0,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+#[enumflags2::bitflags]
+#[derive(Copy, Clone)]
+#[repr(u8)]
+enum Foo {
+    A = 1 << 0,
+    B = 1 << 1,
+}
+
+fn main() {
+    let flags = enumflags2::BitFlags::<Foo>::all();
+    let _ = enumflags2::bitflags_match!(flags, {
+        any Foo::{A | B} => 1,
+        contains Foo::A => 2,
+        _ => 3,
+    });
+}